@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+use crate::ai::error::AIError;
+use crate::ai::types::{ChatCompletionRequest, ProviderCredentials};
+
+/// JSONL file in the app data dir that receives audit entries
+const LOG_FILE: &str = "request-log.jsonl";
+const REDACTED: &str = "[REDACTED]";
+/// Keys that are redacted no matter what the settings say (matched case-insensitively).
+/// Custom provider headers often carry gateway keys, so they are hidden as a whole.
+const ALWAYS_REDACTED: [&str; 4] = ["api_key", "authorization", "x-api-key", "extra_headers"];
+
+/// Opt-in local audit log of chat requests. Off by default; nothing leaves the machine.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestLogSettings {
+    pub enabled: bool,
+    /// Extra JSON keys whose values are replaced with "[REDACTED]", on top of API keys
+    /// and Authorization headers, which are always redacted
+    pub redact_keys: Vec<String>,
+}
+
+struct ActiveLog {
+    settings: RequestLogSettings,
+    path: PathBuf,
+}
+
+static ACTIVE_LOG: RwLock<Option<ActiveLog>> = RwLock::new(None);
+/// Serializes appends so concurrent requests don't interleave lines
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Apply logging settings; entries go to `request-log.jsonl` in `app_data_dir`
+pub fn set_request_logging(settings: RequestLogSettings, app_data_dir: &Path) {
+    let active = ActiveLog {
+        settings,
+        path: app_data_dir.join(LOG_FILE),
+    };
+    match ACTIVE_LOG.write() {
+        Ok(mut guard) => *guard = Some(active),
+        Err(poisoned) => *poisoned.into_inner() = Some(active),
+    }
+}
+
+/// Summary of an outgoing request (model, message count, parameters, provider), or None when
+/// logging is off. Message contents are not included.
+pub fn request_summary(request: &ChatCompletionRequest, credentials: &ProviderCredentials) -> Option<serde_json::Value> {
+    let guard = match ACTIVE_LOG.read() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if !guard.as_ref().is_some_and(|active| active.settings.enabled) {
+        return None;
+    }
+
+    let mut params = serde_json::to_value(request).ok()?;
+    if let Some(params) = params.as_object_mut() {
+        params.remove("messages");
+        if let Some(tools) = params.remove("tools") {
+            let names: Vec<serde_json::Value> = tools
+                .as_array()
+                .map(|tools| tools.iter().map(|tool| tool["function"]["name"].clone()).collect())
+                .unwrap_or_default();
+            params.insert("tools".to_string(), serde_json::json!(names));
+        }
+    }
+
+    Some(serde_json::json!({
+        "model": request.model,
+        "message_count": request.messages.len(),
+        "params": params,
+        "provider": serde_json::to_value(credentials).unwrap_or_default(),
+    }))
+}
+
+/// Append one entry: the request summary plus what came back
+pub fn record(kind: &str, request: serde_json::Value, outcome: serde_json::Value) {
+    let (path, redact_keys) = {
+        let guard = match ACTIVE_LOG.read() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        match guard.as_ref() {
+            Some(active) if active.settings.enabled => (active.path.clone(), active.settings.redact_keys.clone()),
+            _ => return,
+        }
+    };
+
+    let mut entry = serde_json::json!({
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "kind": kind,
+        "request": request,
+        "response": outcome,
+    });
+    redact(&mut entry, &redact_keys);
+
+    let _lock = match WRITE_LOCK.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let result = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{}", entry));
+    if let Err(e) = result {
+        eprintln!("[Audit] Failed to write {}: {}", path.display(), e);
+    }
+}
+
+/// Outcome entry for a failed request; `http_status` is set for provider API errors
+pub fn error_outcome(error: &AIError, elapsed: Duration) -> serde_json::Value {
+    let http_status = match error {
+        AIError::ApiError { status, .. } => Some(*status),
+        _ => None,
+    };
+    serde_json::json!({
+        "status": "error",
+        "http_status": http_status,
+        "error": error.to_string(),
+        "duration_ms": elapsed.as_millis() as u64,
+    })
+}
+
+fn redact(value: &mut serde_json::Value, extra_keys: &[String]) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                let key = key.to_lowercase();
+                let redacted = ALWAYS_REDACTED.contains(&key.as_str())
+                    || extra_keys.iter().any(|extra| extra.to_lowercase() == key);
+                if redacted {
+                    *child = serde_json::json!(REDACTED);
+                } else {
+                    redact(child, extra_keys);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                redact(item, extra_keys);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::ai::error::{AIError, AIResult};
+
+/// Consecutive connection failures before a base_url is short-circuited
+const FAILURE_THRESHOLD: u32 = 3;
+/// How long requests are rejected before a probe request is let through
+const COOLDOWN: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    /// Set while a probe is running; a probe older than the cooldown is considered lost
+    probe_started: Option<Instant>,
+}
+
+/// Per-base_url circuit breaker so a hard-down provider fails fast instead of
+/// making every request wait out the full timeout
+#[derive(Default)]
+pub struct CircuitBreaker {
+    states: Mutex<HashMap<String, BreakerState>>,
+}
+
+impl CircuitBreaker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject the request while the circuit is open; once the cooldown has passed,
+    /// let a single probe through and keep rejecting others until it completes
+    pub fn check(&self, base_url: &str) -> AIResult<()> {
+        let mut states = match self.states.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        let Some(state) = states.get_mut(base_url) else {
+            return Ok(());
+        };
+        let Some(open_until) = state.open_until else {
+            return Ok(());
+        };
+
+        let probe_running = state.probe_started.is_some_and(|started| started.elapsed() < COOLDOWN);
+        if Instant::now() < open_until || probe_running {
+            return Err(AIError::CircuitOpen(base_url.to_string()));
+        }
+
+        state.probe_started = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Record the outcome of a request. Only network errors count as failures;
+    /// any response from the provider (even an error status) resets the breaker.
+    pub fn record<T>(&self, base_url: &str, result: &AIResult<T>) {
+        let mut states = match self.states.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        match result {
+            Err(AIError::NetworkError(_)) => {
+                let state = states.entry(base_url.to_string()).or_default();
+                state.consecutive_failures += 1;
+                state.probe_started = None;
+                if state.consecutive_failures >= FAILURE_THRESHOLD {
+                    state.open_until = Some(Instant::now() + COOLDOWN);
+                    eprintln!(
+                        "[AIProxy] Circuit opened for {} after {} consecutive failures",
+                        base_url, state.consecutive_failures
+                    );
+                }
+            }
+            _ => {
+                states.remove(base_url);
+            }
+        }
+    }
+}
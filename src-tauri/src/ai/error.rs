@@ -10,6 +10,53 @@ pub enum AIError {
 
     #[error("Serialization error: {0}")]
     SerializationError(String),
+
+    /// Non-success HTTP response; status and Retry-After are kept for retry decisions
+    #[error("{message}")]
+    ApiError {
+        status: u16,
+        retry_after_secs: Option<u64>,
+        message: String,
+    },
+
+    /// Rejected by the circuit breaker without contacting the provider
+    #[error("Provider temporarily unavailable ({0}), retrying after cooldown")]
+    CircuitOpen(String),
+
+    #[error("Audio is {size_mb:.1} MB, which exceeds the provider's {limit_mb} MB upload limit; use chunked transcription or a local model")]
+    AudioTooLarge { size_mb: f64, limit_mb: u64 },
+}
+
+impl AIError {
+    /// Whether another provider might succeed where this one failed: the request didn't
+    /// get through, was rate limited, or hit a server error
+    pub fn is_failover(&self) -> bool {
+        match self {
+            AIError::NetworkError(_) | AIError::CircuitOpen(_) => true,
+            AIError::ApiError { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// Build an `ApiError` from a non-success response, e.g. "OpenAI API error (429): ..."
+    pub async fn from_response(context: &str, response: reqwest::Response) -> Self {
+        let status = response.status();
+        let retry_after_secs = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok());
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+
+        AIError::ApiError {
+            status: status.as_u16(),
+            retry_after_secs,
+            message: format!("{} ({}): {}", context, status, error_text),
+        }
+    }
 }
 
 impl From<serde_json::Error> for AIError {
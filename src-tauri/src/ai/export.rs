@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use crate::ai::types::{ChatMessage, ContentPart, MessageContent, Role};
+
+/// Output format for exported conversations
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConversationExportFormat {
+    Markdown,
+    Text,
+    Json,
+}
+
+impl ConversationExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Text => "txt",
+            Self::Json => "json",
+        }
+    }
+}
+
+fn role_label(role: &Role) -> &'static str {
+    match role {
+        Role::System => "System",
+        Role::User => "User",
+        Role::Assistant => "Assistant",
+        Role::Tool => "Tool",
+    }
+}
+
+/// Flatten message content to text, rendering image parts as `[image]`
+fn content_text(content: &MessageContent) -> String {
+    match content {
+        MessageContent::Text(text) => text.clone(),
+        MessageContent::Parts(parts) => parts
+            .iter()
+            .map(|part| match part {
+                ContentPart::Text { text } => text.as_str(),
+                ContentPart::ImageUrl { .. } => "[image]",
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// Format a Unix epoch (ms) timestamp in local time
+fn format_timestamp(timestamp_ms: u64) -> Option<String> {
+    use chrono::TimeZone;
+    chrono::Local
+        .timestamp_millis_opt(timestamp_ms as i64)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+/// Render a conversation as Markdown, plain text or JSON.
+/// `timestamps` (Unix epoch ms) are matched to messages by index when supplied.
+pub fn export_conversation(
+    messages: &[ChatMessage],
+    timestamps: Option<&[u64]>,
+    format: ConversationExportFormat,
+) -> Result<String, String> {
+    let timestamp_at = |index: usize| timestamps.and_then(|ts| ts.get(index)).copied();
+
+    if let ConversationExportFormat::Json = format {
+        let entries: Vec<serde_json::Value> = messages
+            .iter()
+            .enumerate()
+            .map(|(index, msg)| {
+                let mut entry = serde_json::to_value(msg).unwrap_or(serde_json::json!({}));
+                if let (Some(ts), Some(obj)) = (timestamp_at(index), entry.as_object_mut()) {
+                    obj.insert("timestamp".to_string(), serde_json::json!(ts));
+                }
+                entry
+            })
+            .collect();
+        return serde_json::to_string_pretty(&entries)
+            .map_err(|e| format!("Failed to serialize conversation: {}", e));
+    }
+
+    let markdown = matches!(format, ConversationExportFormat::Markdown);
+    let mut blocks = Vec::with_capacity(messages.len());
+
+    for (index, msg) in messages.iter().enumerate() {
+        let mut header = role_label(&msg.role).to_string();
+        if let Some(time) = timestamp_at(index).and_then(format_timestamp) {
+            header = format!("{} ({})", header, time);
+        }
+
+        let mut block = if markdown {
+            format!("### {}\n\n", header)
+        } else {
+            format!("{}:\n", header)
+        };
+
+        let text = content_text(&msg.content);
+        if !text.is_empty() {
+            block.push_str(&text);
+            block.push('\n');
+        }
+
+        for call in msg.tool_calls.iter().flatten() {
+            if markdown {
+                block.push_str(&format!(
+                    "\n**Tool call** `{}`\n\n```json\n{}\n```\n",
+                    call.function.name, call.function.arguments
+                ));
+            } else {
+                block.push_str(&format!(
+                    "[tool call] {}({})\n",
+                    call.function.name, call.function.arguments
+                ));
+            }
+        }
+
+        if let Some(call_id) = &msg.tool_call_id {
+            block.push_str(&format!("[result of tool call {}]\n", call_id));
+        }
+
+        blocks.push(block);
+    }
+
+    let separator = if markdown { "\n" } else { "\n---\n\n" };
+    Ok(blocks.join(separator))
+}
@@ -0,0 +1,104 @@
+//! Best-effort repair of truncated JSON from streamed `json_object` responses.
+//!
+//! A stream cut off by an abort or by `max_tokens` usually ends mid-string or mid-object.
+//! Repair closes the open string, drops a dangling comma/key, and closes open brackets.
+//! If that still doesn't parse, it falls back to cutting at the last complete member.
+
+/// Result of a repair attempt
+pub struct RepairedJson {
+    pub text: String,
+    /// True if the input was not valid JSON and had to be changed
+    pub repaired: bool,
+}
+
+/// Strip a surrounding ```json fence that some models add even in JSON mode
+fn strip_code_fence(text: &str) -> &str {
+    let trimmed = text.trim();
+    if let Some(rest) = trimmed.strip_prefix("```") {
+        let rest = rest.trim_start_matches("json");
+        return rest.trim_end_matches("```").trim();
+    }
+    trimmed
+}
+
+fn is_valid(text: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(text).is_ok()
+}
+
+/// Close an unterminated prefix given the bracket stack and string state at its end
+fn close(prefix: &str, stack: &[char], in_string: bool, escaped: bool) -> String {
+    let mut out = prefix.to_string();
+    if escaped {
+        out.pop();
+    }
+    if in_string {
+        out.push('"');
+    }
+
+    let trimmed_len = out.trim_end().len();
+    out.truncate(trimmed_len);
+    if out.ends_with(',') {
+        out.pop();
+    } else if out.ends_with(':') {
+        out.push_str("null");
+    }
+
+    for open in stack.iter().rev() {
+        out.push(if *open == '{' { '}' } else { ']' });
+    }
+    out
+}
+
+/// Attempt to turn `text` into valid JSON. Returns None if it can't be repaired.
+pub fn repair_json(text: &str) -> Option<RepairedJson> {
+    let body = strip_code_fence(text);
+    if is_valid(body) {
+        return Some(RepairedJson {
+            text: body.to_string(),
+            repaired: body != text.trim(),
+        });
+    }
+
+    let mut stack: Vec<char> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    // Byte offsets of commas outside strings, with the bracket stack at that point
+    let mut cut_points: Vec<(usize, Vec<char>)> = Vec::new();
+
+    for (i, c) in body.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => stack.push(c),
+            '}' | ']' => {
+                stack.pop();
+            }
+            ',' => cut_points.push((i, stack.clone())),
+            _ => {}
+        }
+    }
+
+    let closed = close(body, &stack, in_string, escaped);
+    if is_valid(&closed) {
+        return Some(RepairedJson { text: closed, repaired: true });
+    }
+
+    // Drop the incomplete trailing member and retry from the most recent comma backwards
+    for (pos, stack_at) in cut_points.iter().rev().take(8) {
+        let candidate = close(&body[..*pos], stack_at, false, false);
+        if is_valid(&candidate) {
+            return Some(RepairedJson { text: candidate, repaired: true });
+        }
+    }
+
+    None
+}
@@ -0,0 +1,350 @@
+//! Minimal Model Context Protocol client: JSON-RPC 2.0 over a child process's stdio
+//! (newline-delimited messages) or over the HTTP+SSE transport, enough to list and call tools.
+
+use async_trait::async_trait;
+use eventsource_stream::Eventsource;
+use futures::StreamExt;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use crate::ai::tools::ToolHandler;
+use crate::ai::types::{Tool, ToolFunction};
+
+const PROTOCOL_VERSION: &str = "2024-11-05";
+/// How long to wait for a JSON-RPC response (tool calls included)
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// How long an SSE server has to announce its message endpoint
+const ENDPOINT_TIMEOUT: Duration = Duration::from_secs(10);
+
+type PendingMap = HashMap<u64, oneshot::Sender<Result<serde_json::Value, String>>>;
+type PendingRequests = Arc<Mutex<PendingMap>>;
+
+enum Transport {
+    Stdio {
+        stdin: tokio::sync::Mutex<ChildStdin>,
+        // Held so the server process lives as long as the connection (killed on drop)
+        _child: Child,
+    },
+    Sse {
+        client: reqwest::Client,
+        endpoint: reqwest::Url,
+    },
+}
+
+/// Connection to a single MCP server
+pub struct McpClient {
+    transport: Transport,
+    pending: PendingRequests,
+    next_id: AtomicU64,
+    reader: JoinHandle<()>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct McpTool {
+    name: String,
+    description: Option<String>,
+    input_schema: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolsPage {
+    tools: Vec<McpTool>,
+    next_cursor: Option<String>,
+}
+
+impl McpClient {
+    /// Connect and run the initialize handshake. `server_url` is an http(s) URL for the SSE
+    /// transport; anything else is a command line (optionally prefixed with `stdio:`) that is
+    /// spawned and spoken to over stdin/stdout. Arguments are split on whitespace.
+    pub async fn connect(server_url: &str) -> Result<Self, String> {
+        let server_url = server_url.trim();
+        let client = if server_url.starts_with("http://") || server_url.starts_with("https://") {
+            Self::connect_sse(server_url).await?
+        } else {
+            Self::connect_stdio(server_url.strip_prefix("stdio:").unwrap_or(server_url))?
+        };
+
+        client
+            .request(
+                "initialize",
+                serde_json::json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": { "name": "zakip-voice", "version": env!("CARGO_PKG_VERSION") },
+                }),
+            )
+            .await?;
+        client.notify("notifications/initialized", serde_json::json!({})).await?;
+
+        Ok(client)
+    }
+
+    fn connect_stdio(command_line: &str) -> Result<Self, String> {
+        let mut parts = command_line.split_whitespace();
+        let program = parts.next().ok_or("MCP server command is empty")?;
+
+        let mut child = Command::new(program)
+            .args(parts)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to start MCP server '{}': {}", program, e))?;
+
+        let stdin = child.stdin.take().ok_or("MCP server stdin unavailable")?;
+        let stdout = child.stdout.take().ok_or("MCP server stdout unavailable")?;
+
+        let pending: PendingRequests = Arc::new(Mutex::new(HashMap::new()));
+        let reader_pending = Arc::clone(&pending);
+        let reader = tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                dispatch(&reader_pending, &line);
+            }
+            fail_pending(&reader_pending);
+        });
+
+        Ok(Self {
+            transport: Transport::Stdio {
+                stdin: tokio::sync::Mutex::new(stdin),
+                _child: child,
+            },
+            pending,
+            next_id: AtomicU64::new(1),
+            reader,
+        })
+    }
+
+    async fn connect_sse(server_url: &str) -> Result<Self, String> {
+        let base = reqwest::Url::parse(server_url).map_err(|e| format!("Invalid MCP server URL: {}", e))?;
+        let client = crate::http_client::client_builder()
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        let response = client
+            .get(base.clone())
+            .header("Accept", "text/event-stream")
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to MCP server: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("MCP server returned {}", response.status()));
+        }
+
+        // The first `endpoint` event tells us where to POST messages; responses arrive on this stream
+        let (endpoint_tx, endpoint_rx) = oneshot::channel();
+        let pending: PendingRequests = Arc::new(Mutex::new(HashMap::new()));
+        let reader_pending = Arc::clone(&pending);
+        let reader = tokio::spawn(async move {
+            let mut endpoint_tx = Some(endpoint_tx);
+            let mut events = response.bytes_stream().eventsource();
+            while let Some(Ok(event)) = events.next().await {
+                match event.event.as_str() {
+                    "endpoint" => {
+                        if let Some(tx) = endpoint_tx.take() {
+                            let _ = tx.send(event.data);
+                        }
+                    }
+                    _ => dispatch(&reader_pending, &event.data),
+                }
+            }
+            fail_pending(&reader_pending);
+        });
+
+        let endpoint = match tokio::time::timeout(ENDPOINT_TIMEOUT, endpoint_rx).await {
+            Ok(Ok(path)) => base
+                .join(path.trim())
+                .map_err(|e| format!("Invalid MCP message endpoint: {}", e))?,
+            _ => {
+                reader.abort();
+                return Err("MCP server did not announce a message endpoint".to_string());
+            }
+        };
+
+        Ok(Self {
+            transport: Transport::Sse { client, endpoint },
+            pending,
+            next_id: AtomicU64::new(1),
+            reader,
+        })
+    }
+
+    async fn send(&self, message: serde_json::Value) -> Result<(), String> {
+        match &self.transport {
+            Transport::Stdio { stdin, .. } => {
+                let mut line = message.to_string();
+                line.push('\n');
+                let mut stdin = stdin.lock().await;
+                stdin
+                    .write_all(line.as_bytes())
+                    .await
+                    .map_err(|e| format!("Failed to write to MCP server: {}", e))?;
+                stdin.flush().await.map_err(|e| format!("Failed to write to MCP server: {}", e))
+            }
+            Transport::Sse { client, endpoint } => {
+                let response = client
+                    .post(endpoint.clone())
+                    .json(&message)
+                    .send()
+                    .await
+                    .map_err(|e| format!("Failed to send to MCP server: {}", e))?;
+                if !response.status().is_success() {
+                    return Err(format!("MCP server returned {}", response.status()));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn request(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        lock(&self.pending).insert(id, tx);
+
+        let message = serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        if let Err(e) = self.send(message).await {
+            lock(&self.pending).remove(&id);
+            return Err(e);
+        }
+
+        match tokio::time::timeout(REQUEST_TIMEOUT, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err("MCP server disconnected".to_string()),
+            Err(_) => {
+                lock(&self.pending).remove(&id);
+                Err(format!("MCP request '{}' timed out", method))
+            }
+        }
+    }
+
+    async fn notify(&self, method: &str, params: serde_json::Value) -> Result<(), String> {
+        self.send(serde_json::json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+            .await
+    }
+
+    /// Fetch every tool the server exposes, mapped onto the chat `Tool` type
+    pub async fn list_tools(&self) -> Result<Vec<Tool>, String> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = match &cursor {
+                Some(cursor) => serde_json::json!({ "cursor": cursor }),
+                None => serde_json::json!({}),
+            };
+            let page: ToolsPage = serde_json::from_value(self.request("tools/list", params).await?)
+                .map_err(|e| format!("Invalid tools/list response: {}", e))?;
+
+            tools.extend(page.tools.into_iter().map(|tool| Tool {
+                tool_type: "function".to_string(),
+                function: ToolFunction {
+                    name: tool.name,
+                    description: tool.description,
+                    parameters: tool
+                        .input_schema
+                        .unwrap_or_else(|| serde_json::json!({ "type": "object", "properties": {} })),
+                },
+            }));
+
+            match page.next_cursor {
+                Some(next) if !next.is_empty() => cursor = Some(next),
+                _ => return Ok(tools),
+            }
+        }
+    }
+
+    /// Invoke a tool and flatten its text content; `isError` results are returned as errors
+    pub async fn call_tool(&self, name: &str, arguments: serde_json::Value) -> Result<String, String> {
+        let result = self
+            .request("tools/call", serde_json::json!({ "name": name, "arguments": arguments }))
+            .await?;
+
+        let text = result["content"]
+            .as_array()
+            .map(|blocks| {
+                blocks
+                    .iter()
+                    .map(|block| match block["text"].as_str() {
+                        Some(text) => text.to_string(),
+                        // Images and resources are passed through as JSON
+                        None => block.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .unwrap_or_default();
+
+        if result["isError"].as_bool().unwrap_or(false) {
+            Err(text)
+        } else {
+            Ok(text)
+        }
+    }
+}
+
+impl Drop for McpClient {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// Executes one MCP tool through the connected server
+pub struct McpToolHandler {
+    pub client: Arc<McpClient>,
+    pub name: String,
+}
+
+#[async_trait]
+impl ToolHandler for McpToolHandler {
+    async fn call(&self, arguments: serde_json::Value) -> Result<String, String> {
+        self.client.call_tool(&self.name, arguments).await
+    }
+}
+
+fn lock(pending: &PendingRequests) -> std::sync::MutexGuard<'_, PendingMap> {
+    match pending.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Route a JSON-RPC response to its waiting request; notifications and server requests are ignored
+fn dispatch(pending: &PendingRequests, raw: &str) {
+    let Ok(message) = serde_json::from_str::<serde_json::Value>(raw) else {
+        return;
+    };
+    if message.get("method").is_some() {
+        return;
+    }
+    let Some(id) = message["id"].as_u64() else {
+        return;
+    };
+    let Some(tx) = lock(pending).remove(&id) else {
+        return;
+    };
+
+    let result = match message.get("error") {
+        Some(error) => Err(format!(
+            "MCP error {}: {}",
+            error["code"],
+            error["message"].as_str().unwrap_or("unknown error")
+        )),
+        None => Ok(message.get("result").cloned().unwrap_or(serde_json::Value::Null)),
+    };
+    let _ = tx.send(result);
+}
+
+/// The connection is gone: wake every waiting request with an error
+fn fail_pending(pending: &PendingRequests) {
+    for (_, tx) in lock(pending).drain() {
+        let _ = tx.send(Err("MCP server disconnected".to_string()));
+    }
+}
@@ -1,8 +1,19 @@
+pub mod audit;
+pub mod circuit_breaker;
 pub mod error;
+pub mod export;
+pub mod json_repair;
+pub mod mcp;
 pub mod types;
 pub mod provider;
 pub mod providers;
 pub mod proxy;
+pub mod rate_limiter;
+pub mod response_cache;
+pub mod retry;
+pub mod tokens;
+pub mod tools;
 
 pub use types::*;
 pub use proxy::AIProxy;
+pub use retry::RetryConfig;
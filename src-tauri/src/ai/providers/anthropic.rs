@@ -0,0 +1,449 @@
+use async_trait::async_trait;
+use eventsource_stream::Eventsource;
+use futures::{Stream, StreamExt};
+use reqwest::Client;
+use std::time::Duration;
+use serde::Deserialize;
+use crate::ai::error::{AIError, AIResult};
+use crate::ai::provider::AIProvider;
+use crate::ai::types::{
+    ChatCompletionRequest, ChatCompletionResponse, ChatMessage, Choice, ContentPart, FunctionCall,
+    FunctionCallDelta, MessageContent, ProviderCredentials, Role, StreamChunk, ToolCall, ToolCallAccumulator,
+    ToolCallDelta, Usage, extract_model_id,
+};
+
+pub(crate) const ANTHROPIC_VERSION: &str = "2023-06-01";
+/// `max_tokens` is required by the Messages API
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+pub struct AnthropicProvider {
+    api_key: String,
+    base_url: String,
+    request_timeout: Duration,
+    client: Client,
+}
+
+impl AnthropicProvider {
+    /// Create provider from per-request credentials
+    pub fn from_credentials(credentials: ProviderCredentials) -> AIResult<Self> {
+        let client = credentials.http_client().map_err(AIError::ProviderError)?;
+
+        Ok(Self {
+            request_timeout: Duration::from_secs(credentials.request_timeout_secs()),
+            api_key: credentials.api_key,
+            base_url: credentials.base_url,
+            client,
+        })
+    }
+
+    fn messages_url(&self) -> String {
+        format!("{}/messages", self.base_url.trim_end_matches('/'))
+    }
+
+    /// Map an OpenAI-style request onto the /v1/messages schema
+    fn build_body(request: &ChatCompletionRequest, stream: bool) -> serde_json::Value {
+        let mut system_parts = Vec::new();
+        let mut messages: Vec<serde_json::Value> = Vec::new();
+
+        for msg in &request.messages {
+            let (role, blocks) = match msg.role {
+                Role::System => {
+                    system_parts.push(content_to_text(&msg.content));
+                    continue;
+                }
+                Role::User => ("user", content_blocks(&msg.content)),
+                Role::Assistant => {
+                    let mut blocks = content_blocks(&msg.content);
+                    for call in msg.tool_calls.iter().flatten() {
+                        let input = serde_json::from_str::<serde_json::Value>(&call.function.arguments)
+                            .unwrap_or_else(|_| serde_json::json!({}));
+                        blocks.push(serde_json::json!({
+                            "type": "tool_use",
+                            "id": call.id,
+                            "name": call.function.name,
+                            "input": input,
+                        }));
+                    }
+                    ("assistant", blocks)
+                }
+                // Tool results are sent back as user turns
+                Role::Tool => (
+                    "user",
+                    vec![serde_json::json!({
+                        "type": "tool_result",
+                        "tool_use_id": msg.tool_call_id.clone().unwrap_or_default(),
+                        "content": content_to_text(&msg.content),
+                    })],
+                ),
+            };
+
+            if blocks.is_empty() {
+                continue;
+            }
+
+            // Merge consecutive turns of the same role (e.g. several tool results)
+            match messages.last_mut() {
+                Some(last) if last["role"] == role => {
+                    if let Some(content) = last["content"].as_array_mut() {
+                        content.extend(blocks);
+                    }
+                }
+                _ => messages.push(serde_json::json!({ "role": role, "content": blocks })),
+            }
+        }
+
+        // A trailing assistant prefill must not end with whitespace
+        if let Some(last) = messages.last_mut() {
+            if last["role"] == "assistant" {
+                if let Some(text) = last["content"]
+                    .as_array_mut()
+                    .and_then(|blocks| blocks.last_mut())
+                    .and_then(|block| block.get_mut("text"))
+                {
+                    if let Some(trimmed) = text.as_str().map(|t| t.trim_end().to_string()) {
+                        *text = serde_json::json!(trimmed);
+                    }
+                }
+            }
+        }
+
+        let mut body = serde_json::json!({
+            "model": extract_model_id(&request.model),
+            "messages": messages,
+            "max_tokens": request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            "stream": stream,
+        });
+
+        if !system_parts.is_empty() {
+            body["system"] = serde_json::json!(system_parts.join("\n\n"));
+        }
+        if let Some(temp) = request.temperature {
+            body["temperature"] = serde_json::json!(temp);
+        }
+        if let Some(top_p) = request.top_p {
+            body["top_p"] = serde_json::json!(top_p);
+        }
+        if let Some(stop) = &request.stop {
+            body["stop_sequences"] = serde_json::json!(stop.to_vec());
+        }
+        // The Messages API has no penalties, seed or n; they are dropped rather than rejected
+        if let Some(tools) = &request.tools {
+            if !tools.is_empty() {
+                let tools: Vec<serde_json::Value> = tools
+                    .iter()
+                    .map(|tool| {
+                        serde_json::json!({
+                            "name": tool.function.name,
+                            "description": tool.function.description,
+                            "input_schema": tool.function.parameters,
+                        })
+                    })
+                    .collect();
+                body["tools"] = serde_json::json!(tools);
+                if request.parallel_tool_calls == Some(false) {
+                    body["tool_choice"] = serde_json::json!({
+                        "type": "auto",
+                        "disable_parallel_tool_use": true,
+                    });
+                }
+            }
+        }
+        if let Some(extra) = &request.extra_params {
+            for (k, v) in extra {
+                body[k] = v.clone();
+            }
+        }
+
+        body
+    }
+
+    /// Streamed responses are only bounded by the client's idle timeout
+    async fn send(&self, body: &serde_json::Value, streaming: bool) -> AIResult<reqwest::Response> {
+        let mut builder = self
+            .client
+            .post(self.messages_url())
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .header("Content-Type", "application/json")
+            .json(body);
+        if !streaming {
+            builder = builder.timeout(self.request_timeout);
+        }
+        let response = builder.send().await?;
+
+        if !response.status().is_success() {
+            return Err(AIError::from_response("Anthropic API error", response).await);
+        }
+
+        Ok(response)
+    }
+}
+
+/// Flatten message content to plain text (image parts are dropped)
+fn content_to_text(content: &MessageContent) -> String {
+    match content {
+        MessageContent::Text(text) => text.clone(),
+        MessageContent::Parts(parts) => parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::Text { text } => Some(text.as_str()),
+                ContentPart::ImageUrl { .. } => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// Convert message content into Anthropic content blocks
+fn content_blocks(content: &MessageContent) -> Vec<serde_json::Value> {
+    match content {
+        MessageContent::Text(text) if text.is_empty() => Vec::new(),
+        MessageContent::Text(text) => vec![serde_json::json!({ "type": "text", "text": text })],
+        MessageContent::Parts(parts) => parts
+            .iter()
+            .map(|part| match part {
+                ContentPart::Text { text } => serde_json::json!({ "type": "text", "text": text }),
+                ContentPart::ImageUrl { image_url } => image_block(&image_url.url),
+            })
+            .collect(),
+    }
+}
+
+/// Images arrive as data URIs or remote URLs
+fn image_block(url: &str) -> serde_json::Value {
+    if let Some((media_type, data)) = url
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(";base64,"))
+    {
+        serde_json::json!({
+            "type": "image",
+            "source": { "type": "base64", "media_type": media_type, "data": data },
+        })
+    } else {
+        serde_json::json!({
+            "type": "image",
+            "source": { "type": "url", "url": url },
+        })
+    }
+}
+
+/// Map Anthropic stop reasons onto OpenAI finish reasons
+fn finish_reason(stop_reason: &str) -> String {
+    match stop_reason {
+        "end_turn" | "stop_sequence" => "stop",
+        "max_tokens" => "length",
+        "tool_use" => "tool_calls",
+        other => other,
+    }
+    .to_string()
+}
+
+#[derive(Deserialize)]
+struct MessagesResponse {
+    id: String,
+    model: String,
+    content: Vec<ResponseBlock>,
+    stop_reason: Option<String>,
+    usage: MessagesUsage,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ResponseBlock {
+    Text { text: String },
+    ToolUse { id: String, name: String, input: serde_json::Value },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize, Default)]
+struct MessagesUsage {
+    #[serde(default)]
+    input_tokens: u32,
+    #[serde(default)]
+    output_tokens: u32,
+}
+
+impl MessagesUsage {
+    fn to_usage(&self) -> Usage {
+        Usage {
+            prompt_tokens: self.input_tokens,
+            completion_tokens: self.output_tokens,
+            total_tokens: self.input_tokens + self.output_tokens,
+            search_context_size: None,
+            cost: None,
+            extra: None,
+        }
+    }
+}
+
+#[async_trait]
+impl AIProvider for AnthropicProvider {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> AIResult<ChatCompletionResponse> {
+        let body = Self::build_body(&request, false);
+        let response = self.send(&body, false).await?;
+        let response_text = response.text().await?;
+        let message: MessagesResponse = serde_json::from_str(&response_text)?;
+
+        let mut text = String::new();
+        let mut tool_calls = Vec::new();
+        for block in message.content {
+            match block {
+                ResponseBlock::Text { text: block_text } => text.push_str(&block_text),
+                ResponseBlock::ToolUse { id, name, input } => tool_calls.push(ToolCall {
+                    id,
+                    tool_type: "function".to_string(),
+                    function: FunctionCall {
+                        name,
+                        arguments: input.to_string(),
+                    },
+                }),
+                ResponseBlock::Other => {}
+            }
+        }
+
+        let created = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        Ok(ChatCompletionResponse {
+            id: message.id,
+            object: "chat.completion".to_string(),
+            created,
+            model: message.model,
+            choices: vec![Choice {
+                index: 0,
+                message: ChatMessage {
+                    role: Role::Assistant,
+                    content: MessageContent::Text(text),
+                    name: None,
+                    tool_call_id: None,
+                    tool_calls: if tool_calls.is_empty() { None } else { Some(tool_calls) },
+                    audio: None,
+                },
+                finish_reason: message.stop_reason.as_deref().map(finish_reason),
+            }],
+            usage: message.usage.to_usage(),
+            citations: None,
+            search_results: None,
+            cached: false,
+            provider_index: None,
+            extra: None,
+        })
+    }
+
+    async fn chat_completion_stream(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> AIResult<Box<dyn Stream<Item = AIResult<StreamChunk>> + Send + Unpin>> {
+        let body = Self::build_body(&request, true);
+        let response = self.send(&body, true).await?;
+
+        // Prompt tokens arrive in message_start, completion tokens in message_delta
+        let mut input_tokens = 0u32;
+        // tool_use blocks are mapped onto OpenAI-style tool-call deltas, keyed by content block index
+        let mut tool_calls = ToolCallAccumulator::default();
+        let stream = response
+            .bytes_stream()
+            .eventsource()
+            .map(move |event| {
+                let event = event.map_err(|e| AIError::ProviderError(format!("Stream error: {}", e)))?;
+                let data: serde_json::Value = serde_json::from_str(&event.data)
+                    .map_err(|e| AIError::ProviderError(format!("Failed to parse chunk: {}", e)))?;
+
+                let mut chunk = StreamChunk::default();
+                let index = data["index"].as_u64().unwrap_or(0) as u32;
+
+                match data["type"].as_str().unwrap_or_default() {
+                    "message_start" => {
+                        input_tokens = data["message"]["usage"]["input_tokens"].as_u64().unwrap_or(0) as u32;
+                    }
+                    "content_block_start" => {
+                        let block = &data["content_block"];
+                        if block["type"] == "tool_use" {
+                            let delta = ToolCallDelta {
+                                index,
+                                id: block["id"].as_str().map(str::to_string),
+                                tool_type: Some("function".to_string()),
+                                function: Some(FunctionCallDelta {
+                                    name: block["name"].as_str().map(str::to_string),
+                                    arguments: None,
+                                }),
+                            };
+                            tool_calls.push(&delta);
+                            chunk.tool_call_deltas = Some(vec![delta]);
+                        }
+                    }
+                    "content_block_delta" => match data["delta"]["type"].as_str().unwrap_or_default() {
+                        "text_delta" => {
+                            chunk.content = data["delta"]["text"].as_str().unwrap_or_default().to_string();
+                        }
+                        "input_json_delta" => {
+                            let delta = ToolCallDelta {
+                                index,
+                                id: None,
+                                tool_type: None,
+                                function: Some(FunctionCallDelta {
+                                    name: None,
+                                    arguments: data["delta"]["partial_json"].as_str().map(str::to_string),
+                                }),
+                            };
+                            tool_calls.push(&delta);
+                            chunk.tool_call_deltas = Some(vec![delta]);
+                        }
+                        _ => {}
+                    },
+                    "message_delta" => {
+                        chunk.tool_calls = tool_calls.take().map(|calls| {
+                            // Tools without input stream no JSON at all
+                            calls
+                                .into_iter()
+                                .map(|mut call| {
+                                    if call.function.arguments.is_empty() {
+                                        call.function.arguments = "{}".to_string();
+                                    }
+                                    call
+                                })
+                                .collect()
+                        });
+                        let usage = MessagesUsage {
+                            input_tokens,
+                            output_tokens: data["usage"]["output_tokens"].as_u64().unwrap_or(0) as u32,
+                        };
+                        chunk.usage = Some(usage.to_usage());
+                    }
+                    "error" => {
+                        return Err(AIError::ProviderError(format!(
+                            "Anthropic stream error: {}",
+                            data["error"]["message"].as_str().unwrap_or("unknown error")
+                        )));
+                    }
+                    _ => {}
+                }
+
+                Ok(chunk)
+            });
+
+        Ok(Box::new(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use futures::Stream;
+use reqwest::Client;
+use std::time::Duration;
+use crate::ai::error::{AIError, AIResult};
+use crate::ai::provider::AIProvider;
+use crate::ai::providers::openai::{chat_body, chat_chunk_stream};
+use crate::ai::types::{ChatCompletionRequest, ChatCompletionResponse, ProviderCredentials, StreamChunk, extract_model_id};
+
+/// Used when the credentials don't pin an api-version
+pub(crate) const DEFAULT_API_VERSION: &str = "2024-10-21";
+
+/// Azure OpenAI: the OpenAI wire format, routed per deployment and authenticated with `api-key`.
+/// `base_url` is the resource endpoint (e.g. `https://my-resource.openai.azure.com`) and the
+/// model id is the deployment name.
+pub struct AzureOpenAIProvider {
+    api_key: String,
+    endpoint: String,
+    api_version: String,
+    request_timeout: Duration,
+    stream_usage: bool,
+    client: Client,
+}
+
+impl AzureOpenAIProvider {
+    /// Create provider from per-request credentials
+    pub fn from_credentials(credentials: ProviderCredentials) -> AIResult<Self> {
+        let client = credentials.http_client().map_err(AIError::ProviderError)?;
+
+        Ok(Self {
+            request_timeout: Duration::from_secs(credentials.request_timeout_secs()),
+            api_version: credentials
+                .api_version
+                .filter(|version| !version.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_API_VERSION.to_string()),
+            stream_usage: credentials.stream_usage(),
+            api_key: credentials.api_key,
+            endpoint: credentials.base_url,
+            client,
+        })
+    }
+
+    fn chat_url(&self, model: &str) -> String {
+        format!(
+            "{}/openai/deployments/{}/chat/completions?api-version={}",
+            self.endpoint.trim_end_matches('/'),
+            extract_model_id(model),
+            self.api_version
+        )
+    }
+}
+
+#[async_trait]
+impl AIProvider for AzureOpenAIProvider {
+    fn name(&self) -> &str {
+        "azure"
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> AIResult<ChatCompletionResponse> {
+        let url = self.chat_url(&request.model);
+        let body = chat_body(request, false);
+
+        let response = self
+            .client
+            .post(&url)
+            .header("api-key", &self.api_key)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .timeout(self.request_timeout)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(AIError::from_response("Azure OpenAI API error", response).await);
+        }
+
+        let response_text = response.text().await?;
+        let completion: ChatCompletionResponse = serde_json::from_str(&response_text)?;
+
+        Ok(completion)
+    }
+
+    async fn chat_completion_stream(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> AIResult<Box<dyn Stream<Item = AIResult<StreamChunk>> + Send + Unpin>> {
+        let url = self.chat_url(&request.model);
+        let mut body = chat_body(request, true);
+        if self.stream_usage {
+            body["stream_options"] = serde_json::json!({"include_usage": true});
+        }
+
+        let response = self
+            .client
+            .post(&url)
+            .header("api-key", &self.api_key)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(AIError::from_response("Azure OpenAI API error", response).await);
+        }
+
+        Ok(chat_chunk_stream(response))
+    }
+}
@@ -1,3 +1,7 @@
+pub mod anthropic;
+pub mod azure;
 pub mod openai;
 
+pub use anthropic::AnthropicProvider;
+pub use azure::AzureOpenAIProvider;
 pub use openai::OpenAIProvider;
@@ -2,28 +2,66 @@ use async_trait::async_trait;
 use eventsource_stream::Eventsource;
 use futures::{Stream, StreamExt};
 use reqwest::Client;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
 use crate::ai::error::{AIError, AIResult};
 use crate::ai::provider::AIProvider;
-use crate::ai::types::{ChatCompletionRequest, ChatCompletionResponse, ChatCompletionChunk, ProviderCredentials, StreamChunk, extract_model_id};
+use crate::ai::types::{ChatCompletionRequest, ChatCompletionResponse, ChatCompletionChunk, ProviderCredentials, RealtimeTranscriptionEvent, Role, StreamChunk, ToolCallAccumulator, extract_model_id};
+
+/// OpenAI's upload limit for /audio/transcriptions
+const DEFAULT_MAX_AUDIO_UPLOAD_MB: u64 = 25;
+/// Sample rate the realtime API expects for `pcm16` input
+pub const REALTIME_SAMPLE_RATE: u32 = 24000;
+
+type WsIncoming = futures::stream::SplitStream<
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>,
+>;
+
+/// Read side of a realtime transcription session; dropping it stops the audio writer
+struct RealtimeSession {
+    incoming: WsIncoming,
+    writer: tokio::task::JoinHandle<()>,
+    final_commit_sent: Arc<AtomicBool>,
+    /// Set once the server answered the final commit
+    final_commit_acked: bool,
+    /// Utterances committed but not transcribed yet
+    pending_items: HashSet<String>,
+    done: bool,
+}
+
+impl Drop for RealtimeSession {
+    fn drop(&mut self) {
+        self.writer.abort();
+    }
+}
 
 pub struct OpenAIProvider {
     api_key: String,
     base_url: String,
+    max_audio_upload_mb: u64,
+    request_timeout: Duration,
+    /// Applied after the provider's own headers, so they win on conflicts
+    extra_headers: reqwest::header::HeaderMap,
+    stream_usage: bool,
     client: Client,
 }
 
 impl OpenAIProvider {
     /// Create provider from per-request credentials (new preferred method)
     pub fn from_credentials(credentials: ProviderCredentials) -> AIResult<Self> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(120))
-            .build()
-            .map_err(|e| AIError::ProviderError(format!("Failed to create HTTP client: {}", e)))?;
+        let client = credentials.http_client().map_err(AIError::ProviderError)?;
+        let extra_headers = credentials.extra_header_map().map_err(AIError::ProviderError)?;
 
         Ok(Self {
+            request_timeout: Duration::from_secs(credentials.request_timeout_secs()),
+            extra_headers,
+            stream_usage: credentials.stream_usage(),
             api_key: credentials.api_key,
             base_url: credentials.base_url,
+            max_audio_upload_mb: credentials.max_audio_upload_mb.unwrap_or(DEFAULT_MAX_AUDIO_UPLOAD_MB),
             client,
         })
     }
@@ -33,6 +71,63 @@ impl OpenAIProvider {
     }
 }
 
+/// Model families that take `reasoning_effort`, matched as id prefixes
+const REASONING_MODEL_PREFIXES: &[&str] = &["o1", "o3", "o4", "gpt-5", "codex-"];
+/// Models that reject `reasoning_effort`; checked before the reasoning prefixes
+const NON_REASONING_MODEL_PREFIXES: &[&str] = &["o1-mini", "o1-preview", "gpt-5-chat", "gpt-4", "gpt-3.5", "chatgpt-"];
+
+/// Models that support `json_schema` structured outputs, matched as id prefixes
+const JSON_SCHEMA_MODEL_PREFIXES: &[&str] = &["gpt-4o", "gpt-4.1", "gpt-5", "o1", "o3", "o4"];
+/// Models that predate `json_schema`; checked first. "gpt-4-" covers gpt-4-turbo and dated gpt-4s.
+const NO_JSON_SCHEMA_MODEL_PREFIXES: &[&str] =
+    &["gpt-4o-2024-05-13", "o1-mini", "o1-preview", "gpt-4-", "gpt-3.5", "chatgpt-"];
+
+/// Look `model` up in a capability table: Some(false) if it matches an `unsupported` prefix,
+/// Some(true) if it matches a `supported` one, None if it isn't listed.
+/// Router prefixes like "openai/" are ignored.
+fn model_capability(model: &str, supported: &[&str], unsupported: &[&str]) -> Option<bool> {
+    let name = model.rsplit('/').next().unwrap_or(model).to_ascii_lowercase();
+    if unsupported.iter().any(|prefix| name.starts_with(prefix)) {
+        Some(false)
+    } else if supported.iter().any(|prefix| name.starts_with(prefix)) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Whether `model` accepts `reasoning_effort` (None for models not in the table)
+fn supports_reasoning_effort(model: &str) -> Option<bool> {
+    model_capability(model, REASONING_MODEL_PREFIXES, NON_REASONING_MODEL_PREFIXES)
+}
+
+/// Whether `model` supports `json_schema` response formats (None for models not in the table)
+pub(crate) fn supports_json_schema(model: &str) -> Option<bool> {
+    if model.rsplit('/').next().is_some_and(|name| name.eq_ignore_ascii_case("gpt-4")) {
+        return Some(false);
+    }
+    model_capability(model, JSON_SCHEMA_MODEL_PREFIXES, NO_JSON_SCHEMA_MODEL_PREFIXES)
+}
+
+/// Sampling knobs shared by streaming and non-streaming chat bodies, only included if present
+fn apply_sampling_params(body: &mut serde_json::Value, request: &ChatCompletionRequest) {
+    if let Some(top_p) = request.top_p {
+        body["top_p"] = serde_json::json!(top_p);
+    }
+    if let Some(frequency_penalty) = request.frequency_penalty {
+        body["frequency_penalty"] = serde_json::json!(frequency_penalty);
+    }
+    if let Some(presence_penalty) = request.presence_penalty {
+        body["presence_penalty"] = serde_json::json!(presence_penalty);
+    }
+    if let Some(seed) = request.seed {
+        body["seed"] = serde_json::json!(seed);
+    }
+    if let Some(stop) = &request.stop {
+        body["stop"] = serde_json::json!(stop);
+    }
+}
+
 #[async_trait]
 impl AIProvider for OpenAIProvider {
     fn name(&self) -> &str {
@@ -59,88 +154,21 @@ impl AIProvider for OpenAIProvider {
         let base_url = base_url_string.trim_end_matches('/');
         let url = format!("{}/chat/completions", base_url);
 
-        // Filter messages - remove tool-related messages and fields for providers that don't support them
-        let filtered_messages: Vec<serde_json::Value> = request.messages.iter()
-            .filter_map(|msg| {
-                let mut msg_json = serde_json::to_value(msg).unwrap_or(serde_json::json!({}));
-
-                // Skip tool messages entirely
-                if let Some(role) = msg_json.get("role").and_then(|r| r.as_str()) {
-                    if role == "tool" {
-                        return None;
-                    }
-                }
-
-                // Remove tool-specific fields from other messages
-                if let Some(obj) = msg_json.as_object_mut() {
-                    obj.remove("tool_calls");
-                    obj.remove("tool_call_id");
-                }
-
-                Some(msg_json)
-            })
-            .collect();
-
-        // Build request body - only include non-None fields
-        // Extract actual model ID from composite (e.g., "openai::gpt-4" -> "gpt-4")
-        let actual_model = extract_model_id(&request.model);
-        let mut body = serde_json::json!({
-            "model": actual_model,
-            "messages": filtered_messages,
-            "stream": false,
-        });
-
-        // Add optional fields only if present
-        if let Some(temp) = request.temperature {
-            body["temperature"] = serde_json::json!(temp);
-        }
-        if let Some(max_tokens) = request.max_tokens {
-            body["max_tokens"] = serde_json::json!(max_tokens);
-        }
-        if let Some(tools) = request.tools {
-            if !tools.is_empty() {
-                body["tools"] = serde_json::json!(tools);
-            }
-        }
-        if let Some(tool_ids) = request.tool_ids {
-            if !tool_ids.is_empty() {
-                body["tool_ids"] = serde_json::json!(tool_ids);
-            }
-        }
-        if let Some(response_format) = request.response_format {
-            body["response_format"] = serde_json::json!(response_format);
-        }
-        if let Some(reasoning_effort) = request.reasoning_effort {
-            // Only send reasoning_effort for o1 models and o3 models
-            if actual_model.starts_with("o1") || actual_model.starts_with("o3") {
-                body["reasoning_effort"] = serde_json::json!(reasoning_effort);
-            }
-        }
-        if let Some(extra) = request.extra_params {
-            for (k, v) in extra {
-                body[k] = v;
-            }
-        }
+        let body = chat_body(request, false);
 
         let response = self
             .client
             .post(&url)
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
+            .headers(self.extra_headers.clone())
             .json(&body)
+            .timeout(self.request_timeout)
             .send()
             .await?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(AIError::ProviderError(format!(
-                "OpenAI API error ({}): {}",
-                status, error_text
-            )));
+            return Err(AIError::from_response("OpenAI API error", response).await);
         }
 
         let response_text = response.text().await?;
@@ -157,139 +185,208 @@ impl AIProvider for OpenAIProvider {
         let base_url = base_url_string.trim_end_matches('/');
         let url = format!("{}/chat/completions", base_url);
 
-        // Filter messages - remove tool-related messages and fields for providers that don't support them
-        let filtered_messages: Vec<serde_json::Value> = request.messages.iter()
-            .filter_map(|msg| {
-                let mut msg_json = serde_json::to_value(msg).unwrap_or(serde_json::json!({}));
+        let mut body = chat_body(request, true);
+        if self.stream_usage {
+            body["stream_options"] = serde_json::json!({"include_usage": true});
+        }
 
-                // Skip tool messages entirely
-                if let Some(role) = msg_json.get("role").and_then(|r| r.as_str()) {
-                    if role == "tool" {
-                        return None;
-                    }
-                }
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .headers(self.extra_headers.clone())
+            .json(&body)
+            .send()
+            .await?;
 
-                // Remove tool-specific fields from other messages
-                if let Some(obj) = msg_json.as_object_mut() {
+        if !response.status().is_success() {
+            return Err(AIError::from_response("OpenAI API error", response).await);
+        }
+
+        Ok(chat_chunk_stream(response))
+    }
+}
+
+/// Build an OpenAI-format chat completions body. Shared with other providers that speak
+/// the same wire format (Azure OpenAI).
+pub(crate) fn chat_body(request: ChatCompletionRequest, stream: bool) -> serde_json::Value {
+    // Tool history (assistant `tool_calls`, `tool` results) is forwarded when tools are attached,
+    // e.g. on later rounds of a tool loop. Without tools, providers reject it, so it's dropped.
+    let with_tools = request.tools.as_ref().is_some_and(|tools| !tools.is_empty());
+    let filtered_messages: Vec<serde_json::Value> = request.messages.iter()
+        .filter_map(|msg| {
+            let mut msg_json = serde_json::to_value(msg).unwrap_or(serde_json::json!({}));
+
+            if !with_tools && matches!(msg.role, Role::Tool) {
+                return None;
+            }
+
+            if let Some(obj) = msg_json.as_object_mut() {
+                if !with_tools {
                     obj.remove("tool_calls");
                     obj.remove("tool_call_id");
                 }
 
-                Some(msg_json)
-            })
-            .collect();
-
-        // Build request body - only include non-None fields
-        let actual_model = extract_model_id(&request.model);
-        let mut body = serde_json::json!({
-            "model": actual_model,
-            "messages": filtered_messages,
-            "stream": true,
-        });
+                // Previous audio responses are referenced by id only
+                if let Some(audio_id) = msg.audio.as_ref().map(|audio| audio.id.clone()) {
+                    obj.insert("audio".to_string(), serde_json::json!({"id": audio_id}));
+                }
+            }
 
-        // Add optional fields only if present
-        if let Some(temp) = request.temperature {
-            body["temperature"] = serde_json::json!(temp);
+            Some(msg_json)
+        })
+        .collect();
+
+    // Build request body - only include non-None fields
+    // Extract actual model ID from composite (e.g., "openai::gpt-4" -> "gpt-4")
+    let actual_model = extract_model_id(&request.model);
+    let mut body = serde_json::json!({
+        "model": actual_model,
+        "messages": filtered_messages,
+        "stream": stream,
+    });
+
+    // Add optional fields only if present
+    if let Some(temp) = request.temperature {
+        body["temperature"] = serde_json::json!(temp);
+    }
+    if let Some(max_tokens) = request.max_tokens {
+        body["max_tokens"] = serde_json::json!(max_tokens);
+    }
+    if let Some(n) = request.n {
+        body["n"] = serde_json::json!(n);
+    }
+    apply_sampling_params(&mut body, &request);
+    if let Some(tools) = request.tools {
+        if !tools.is_empty() {
+            body["tools"] = serde_json::json!(tools);
+            if let Some(parallel_tool_calls) = request.parallel_tool_calls {
+                body["parallel_tool_calls"] = serde_json::json!(parallel_tool_calls);
+            }
         }
-        if let Some(max_tokens) = request.max_tokens {
-            body["max_tokens"] = serde_json::json!(max_tokens);
+    }
+    if let Some(tool_ids) = request.tool_ids {
+        if !tool_ids.is_empty() {
+            body["tool_ids"] = serde_json::json!(tool_ids);
         }
-        if let Some(tools) = request.tools {
-            if !tools.is_empty() {
-                body["tools"] = serde_json::json!(tools);
-            }
+    }
+    if let Some(response_format) = request.response_format {
+        body["response_format"] = serde_json::json!(response_format);
+    }
+    // Audio output is only requested on buffered completions
+    if !stream {
+        if let Some(modalities) = request.modalities {
+            body["modalities"] = serde_json::json!(modalities);
         }
-        if let Some(tool_ids) = request.tool_ids {
-            if !tool_ids.is_empty() {
-                body["tool_ids"] = serde_json::json!(tool_ids);
-            }
+        if let Some(audio) = request.audio {
+            body["audio"] = serde_json::json!(audio);
         }
-        if let Some(reasoning_effort) = request.reasoning_effort {
-            // Only send reasoning_effort for o1 models and o3 models
-            if actual_model.starts_with("o1") || actual_model.starts_with("o3") {
-                body["reasoning_effort"] = serde_json::json!(reasoning_effort);
-            }
+    }
+    if let Some(reasoning_effort) = request.reasoning_effort {
+        // Known non-reasoning models reject the field; models missing from the table get it
+        // passed through like an extra param, so new releases aren't left without it
+        let send = request
+            .force_reasoning
+            .unwrap_or_else(|| supports_reasoning_effort(actual_model).unwrap_or(true));
+        if send {
+            body["reasoning_effort"] = serde_json::json!(reasoning_effort);
         }
-        if let Some(extra) = request.extra_params {
-            for (k, v) in extra {
-                body[k] = v;
-            }
+    }
+    if let Some(extra) = request.extra_params {
+        for (k, v) in extra {
+            body[k] = v;
         }
+    }
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
+    body
+}
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(AIError::ProviderError(format!(
-                "OpenAI API error ({}): {}",
-                status, error_text
-            )));
-        }
-
-        // Create SSE stream with accumulated response logging
-        let accumulated = Arc::new(Mutex::new(String::new()));
-        let stream = response
-            .bytes_stream()
-            .eventsource()
-            .map(move |event| {
-                match event {
-                    Ok(event) => {
-                        if event.data == "[DONE]" {
-                            return Ok(StreamChunk {
-                                content: String::new(),
-                                citations: None,
-                                search_results: None,
-                                usage: None,
-                            });
-                        }
+/// Map an OpenAI-format SSE response onto `StreamChunk`s
+pub(crate) fn chat_chunk_stream(
+    response: reqwest::Response,
+) -> Box<dyn Stream<Item = AIResult<StreamChunk>> + Send + Unpin> {
+    sse_chunk_stream(response.bytes_stream())
+}
 
-                        // Parse chunk
-                        match serde_json::from_str::<ChatCompletionChunk>(&event.data) {
-                            Ok(chunk) => {
-                                // Extract content from first choice delta
-                                let content = chunk.choices.first()
-                                    .and_then(|choice| choice.delta.content.clone())
-                                    .unwrap_or_default();
-
-                                if !content.is_empty() {
-                                    if let Ok(mut acc) = accumulated.lock() {
-                                        acc.push_str(&content);
-                                    }
-                                }
+/// `chat_chunk_stream` over the raw SSE body
+pub(crate) fn sse_chunk_stream<S, B, E>(body: S) -> Box<dyn Stream<Item = AIResult<StreamChunk>> + Send + Unpin>
+where
+    S: Stream<Item = Result<B, E>> + Send + 'static,
+    B: AsRef<[u8]> + Send + 'static,
+    E: std::fmt::Display + Send + 'static,
+{
+    // Create SSE stream with accumulated response logging
+    let accumulated = Arc::new(Mutex::new(String::new()));
+    let mut tool_calls = ToolCallAccumulator::default();
+    let stream = body
+        .eventsource()
+        .map(move |event| {
+            match event {
+                Ok(event) => {
+                    if event.data == "[DONE]" {
+                        // Some providers end the stream without a finish_reason on the last delta
+                        return Ok(StreamChunk {
+                            tool_calls: tool_calls.take(),
+                            ..Default::default()
+                        });
+                    }
 
-                                // Create StreamChunk with content and metadata
-                                // Citations, search_results, and usage are typically only in final chunk
-                                Ok(StreamChunk {
-                                    content,
-                                    citations: chunk.citations.clone(),
-                                    search_results: chunk.search_results.clone(),
-                                    usage: chunk.usage.clone(),
-                                })
+                    // Parse chunk
+                    match serde_json::from_str::<ChatCompletionChunk>(&event.data) {
+                        Ok(chunk) => {
+                            // With n > 1 each event carries a single choice, tagged by index
+                            let choice = chunk.choices.first();
+                            let choice_index = choice.map(|choice| choice.index).unwrap_or(0);
+
+                            // Extract content from first choice delta
+                            let content = choice
+                                .and_then(|choice| choice.delta.content.clone())
+                                .unwrap_or_default();
+
+                            if !content.is_empty() {
+                                if let Ok(mut acc) = accumulated.lock() {
+                                    acc.push_str(&content);
+                                }
                             }
-                            Err(e) => {
-                                Err(AIError::ProviderError(format!("Failed to parse chunk: {}", e)))
+
+                            // Tool-call arguments arrive fragmented and indexed; forward the
+                            // fragments as they come and the assembled calls once the choice finishes.
+                            // Only the first choice's calls are assembled.
+                            let tool_call_deltas = choice.and_then(|choice| choice.delta.tool_calls.clone());
+                            if choice_index == 0 {
+                                for delta in tool_call_deltas.iter().flatten() {
+                                    tool_calls.push(delta);
+                                }
                             }
+                            let finished = choice_index == 0
+                                && choice.map(|choice| choice.finish_reason.is_some()).unwrap_or(false);
+
+                            // Create StreamChunk with content and metadata
+                            // Citations, search_results, and usage are typically only in final chunk;
+                            // with include_usage, usage arrives in a last chunk without choices
+                            Ok(StreamChunk {
+                                content,
+                                choice_index,
+                                citations: chunk.citations.clone(),
+                                search_results: chunk.search_results.clone(),
+                                usage: chunk.usage.clone(),
+                                tool_call_deltas,
+                                tool_calls: if finished { tool_calls.take() } else { None },
+                            })
+                        }
+                        Err(e) => {
+                            Err(AIError::ProviderError(format!("Failed to parse chunk: {}", e)))
                         }
-                    }
-                    Err(e) => {
-                        Err(AIError::ProviderError(format!("Stream error: {}", e)))
                     }
                 }
-            });
+                Err(e) => {
+                    Err(AIError::ProviderError(format!("Stream error: {}", e)))
+                }
+            }
+        });
 
-        Ok(Box::new(Box::pin(stream)))
-    }
+    Box::new(Box::pin(stream))
 }
 
 // Additional OpenAI-specific methods (not part of the AIProvider trait)
@@ -304,10 +401,55 @@ impl OpenAIProvider {
         let base_url = base_url_string.trim_end_matches('/');
         let url = format!("{}/audio/transcriptions", base_url);
 
-        // Create multipart form with audio file
+        let form = self.transcription_form(audio_data, request)?;
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .headers(self.extra_headers.clone())
+            .multipart(form)
+            .timeout(self.request_timeout)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(AIError::from_response("OpenAI transcription error", response).await);
+        }
+
+        let transcription: crate::ai::types::AudioTranscriptionResponse = response.json().await?;
+
+        Ok(transcription)
+    }
+
+    /// Build the multipart form shared by buffered and streaming transcription.
+    /// Oversized audio is rejected before sending instead of surfacing as a generic 413/400.
+    fn transcription_form(
+        &self,
+        audio_data: Vec<u8>,
+        request: crate::ai::types::AudioTranscriptionRequest,
+    ) -> AIResult<reqwest::multipart::Form> {
+        // Name the upload after its actual container (recordings may be WAV, FLAC or Ogg Opus,
+        // dropped files MP3, M4A, WebM, ...); anything unrecognized is converted to WAV
+        let (audio_data, container) = match crate::audio::format::detect_container(&audio_data) {
+            Some(container) => (audio_data, container),
+            None => {
+                let wav = crate::audio::decode::transcode_to_wav(&audio_data)
+                    .map_err(|e| AIError::ProviderError(format!("Unsupported audio format: {}", e)))?;
+                (wav, crate::audio::format::AudioContainer::Wav)
+            }
+        };
+        // Checked on the bytes actually uploaded, since transcoding to WAV can grow them a lot
+        if audio_data.len() as u64 > self.max_audio_upload_mb * 1024 * 1024 {
+            return Err(AIError::AudioTooLarge {
+                size_mb: audio_data.len() as f64 / (1024.0 * 1024.0),
+                limit_mb: self.max_audio_upload_mb,
+            });
+        }
+
         let part = reqwest::multipart::Part::bytes(audio_data)
-            .file_name("audio.wav")
-            .mime_str("audio/wav")
+            .file_name(format!("audio.{}", container.extension()))
+            .mime_str(container.mime())
             .map_err(|e| AIError::ProviderError(format!("Failed to set MIME type: {}", e)))?;
 
         let actual_model = extract_model_id(&request.model);
@@ -331,30 +473,232 @@ impl OpenAIProvider {
         if let Some(temperature) = request.temperature {
             form = form.text("temperature", temperature.to_string());
         }
+        if let Some(granularities) = request.timestamp_granularities {
+            for granularity in granularities {
+                form = form.text("timestamp_granularities[]", granularity);
+            }
+        }
+
+        Ok(form)
+    }
+
+    /// Transcribe audio with `stream: true`, yielding text deltas as the provider finalizes them.
+    /// Models without streaming transcription support fall back to the buffered request and
+    /// yield the whole text as a single delta.
+    pub async fn transcribe_audio_stream(
+        &self,
+        audio_data: Vec<u8>,
+        request: crate::ai::types::AudioTranscriptionRequest,
+    ) -> AIResult<Box<dyn Stream<Item = AIResult<String>> + Send + Unpin>> {
+        if !crate::ai::types::supports_streaming_transcription(&request.model) {
+            let transcription = self.transcribe_audio(audio_data, request).await?;
+            return Ok(Box::new(futures::stream::iter(vec![Ok(transcription.text)])));
+        }
+
+        let base_url_string = self.get_base_url();
+        let base_url = base_url_string.trim_end_matches('/');
+        let url = format!("{}/audio/transcriptions", base_url);
+
+        let form = self.transcription_form(audio_data, request)?.text("stream", "true");
 
         let response = self
             .client
             .post(&url)
             .header("Authorization", format!("Bearer {}", self.api_key))
+            .headers(self.extra_headers.clone())
             .multipart(form)
             .send()
             .await?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(AIError::ProviderError(format!(
-                "OpenAI transcription error ({}): {}",
-                status, error_text
-            )));
+            return Err(AIError::from_response("OpenAI transcription error", response).await);
         }
 
-        let transcription: crate::ai::types::AudioTranscriptionResponse = response.json().await?;
+        // Events look like {"type":"transcript.text.delta","delta":"..."};
+        // the final "transcript.text.done" repeats the full text, so it is skipped
+        let stream = response
+            .bytes_stream()
+            .eventsource()
+            .filter_map(|event| async move {
+                match event {
+                    Ok(event) => {
+                        if event.data == "[DONE]" {
+                            return None;
+                        }
+                        match serde_json::from_str::<serde_json::Value>(&event.data) {
+                            Ok(value) => {
+                                let event_type = value.get("type").and_then(|t| t.as_str()).unwrap_or("");
+                                if event_type.ends_with(".text.delta") {
+                                    value
+                                        .get("delta")
+                                        .and_then(|d| d.as_str())
+                                        .map(|d| Ok(d.to_string()))
+                                } else {
+                                    None
+                                }
+                            }
+                            Err(e) => Some(Err(AIError::ProviderError(format!(
+                                "Failed to parse transcription event: {}",
+                                e
+                            )))),
+                        }
+                    }
+                    Err(e) => Some(Err(AIError::ProviderError(format!("Stream error: {}", e)))),
+                }
+            });
 
-        Ok(transcription)
+        Ok(Box::new(Box::pin(stream)))
+    }
+
+    /// Open a realtime transcription session and stream `audio` into it: mono PCM16 chunks at
+    /// `REALTIME_SAMPLE_RATE`. The channel is only drained as fast as the socket accepts data,
+    /// so a slow network pushes back on a bounded sender instead of losing audio. Closing the
+    /// channel commits what is left; the returned stream ends once all of it is transcribed.
+    pub async fn transcribe_realtime(
+        &self,
+        request: crate::ai::types::AudioTranscriptionRequest,
+        mut audio: tokio::sync::mpsc::Receiver<Vec<i16>>,
+    ) -> AIResult<Box<dyn Stream<Item = AIResult<RealtimeTranscriptionEvent>> + Send + Unpin>> {
+        use base64::Engine;
+        use futures::SinkExt;
+        use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+        use tokio_tungstenite::tungstenite::http::HeaderValue;
+
+        let base_url_string = self.get_base_url();
+        let base_url = base_url_string.trim_end_matches('/');
+        let ws_base = match base_url.split_once("://") {
+            Some(("https", rest)) => format!("wss://{}", rest),
+            Some(("http", rest)) => format!("ws://{}", rest),
+            _ => base_url.to_string(),
+        };
+        let url = format!("{}/realtime?intent=transcription", ws_base);
+
+        let mut ws_request = url
+            .into_client_request()
+            .map_err(|e| AIError::ProviderError(format!("Invalid realtime URL: {}", e)))?;
+        let auth = HeaderValue::from_str(&format!("Bearer {}", self.api_key))
+            .map_err(|e| AIError::ProviderError(format!("Invalid API key: {}", e)))?;
+        ws_request.headers_mut().insert("Authorization", auth);
+        ws_request.headers_mut().insert("OpenAI-Beta", HeaderValue::from_static("realtime=v1"));
+        for (name, value) in &self.extra_headers {
+            ws_request.headers_mut().insert(name.clone(), value.clone());
+        }
+
+        let (socket, _) = tokio_tungstenite::connect_async(ws_request).await.map_err(|e| match e {
+            tokio_tungstenite::tungstenite::Error::Http(response) => AIError::ApiError {
+                status: response.status().as_u16(),
+                retry_after_secs: None,
+                message: format!("OpenAI realtime transcription error ({})", response.status()),
+            },
+            e => AIError::NetworkError(format!("Failed to connect to realtime transcription: {}", e)),
+        })?;
+        let (mut sink, incoming) = socket.split();
+
+        let mut transcription = serde_json::json!({ "model": extract_model_id(&request.model) });
+        if let Some(language) = request.language {
+            transcription["language"] = serde_json::json!(language);
+        }
+        if let Some(prompt) = request.prompt {
+            transcription["prompt"] = serde_json::json!(prompt);
+        }
+        let session_update = serde_json::json!({
+            "type": "transcription_session.update",
+            "session": {
+                "input_audio_format": "pcm16",
+                "input_audio_transcription": transcription,
+                // Each pause in speech closes an utterance, which is when its text arrives
+                "turn_detection": { "type": "server_vad" },
+            },
+        });
+        sink.send(WsMessage::Text(session_update.to_string()))
+            .await
+            .map_err(|e| AIError::NetworkError(format!("Realtime transcription send failed: {}", e)))?;
+
+        let final_commit_sent = Arc::new(AtomicBool::new(false));
+        let writer_final_commit = Arc::clone(&final_commit_sent);
+        let writer = tokio::spawn(async move {
+            while let Some(chunk) = audio.recv().await {
+                let bytes: Vec<u8> = chunk.iter().flat_map(|sample| sample.to_le_bytes()).collect();
+                let append = serde_json::json!({
+                    "type": "input_audio_buffer.append",
+                    "audio": base64::engine::general_purpose::STANDARD.encode(bytes),
+                });
+                if sink.send(WsMessage::Text(append.to_string())).await.is_err() {
+                    return;
+                }
+            }
+            writer_final_commit.store(true, Ordering::SeqCst);
+            let commit = serde_json::json!({ "type": "input_audio_buffer.commit" });
+            let _ = sink.send(WsMessage::Text(commit.to_string())).await;
+        });
+
+        let session = RealtimeSession {
+            incoming,
+            writer,
+            final_commit_sent,
+            final_commit_acked: false,
+            pending_items: HashSet::new(),
+            done: false,
+        };
+        let stream = futures::stream::unfold(session, |mut session| async move {
+            if session.done {
+                return None;
+            }
+            loop {
+                let message = match session.incoming.next().await? {
+                    Ok(WsMessage::Text(text)) => text,
+                    Ok(_) => continue,
+                    Err(e) => {
+                        session.done = true;
+                        let error = AIError::NetworkError(format!("Realtime transcription connection failed: {}", e));
+                        return Some((Err(error), session));
+                    }
+                };
+                let Ok(event) = serde_json::from_str::<serde_json::Value>(&message) else {
+                    continue;
+                };
+                let after_final_commit = session.final_commit_sent.load(Ordering::SeqCst);
+                let item_id = event["item_id"].as_str().unwrap_or_default().to_string();
+
+                match event["type"].as_str().unwrap_or_default() {
+                    "input_audio_buffer.committed" => {
+                        session.pending_items.insert(item_id);
+                        if after_final_commit {
+                            session.final_commit_acked = true;
+                        }
+                    }
+                    "conversation.item.input_audio_transcription.delta" => {
+                        let delta = event["delta"].as_str().unwrap_or_default().to_string();
+                        return Some((Ok(RealtimeTranscriptionEvent::Delta { item_id, delta }), session));
+                    }
+                    "conversation.item.input_audio_transcription.completed" => {
+                        session.pending_items.remove(&item_id);
+                        session.done = session.final_commit_acked && session.pending_items.is_empty();
+                        let transcript = event["transcript"].as_str().unwrap_or_default().to_string();
+                        return Some((Ok(RealtimeTranscriptionEvent::Completed { item_id, transcript }), session));
+                    }
+                    "conversation.item.input_audio_transcription.failed" | "error" => {
+                        // Nothing left to commit after voice activity detection closed the last utterance
+                        if after_final_commit && event["error"]["code"] == "input_audio_buffer_commit_empty" {
+                            session.final_commit_acked = true;
+                            if session.pending_items.is_empty() {
+                                return None;
+                            }
+                            continue;
+                        }
+                        session.done = true;
+                        let error = AIError::ProviderError(format!(
+                            "OpenAI realtime transcription error: {}",
+                            event["error"]["message"].as_str().unwrap_or("unknown error")
+                        ));
+                        return Some((Err(error), session));
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        Ok(Box::new(Box::pin(stream)))
     }
 
     /// Generate speech from text using TTS
@@ -362,6 +706,81 @@ impl OpenAIProvider {
         &self,
         request: crate::ai::types::TextToSpeechRequest,
     ) -> AIResult<Vec<u8>> {
+        let response = self.speech_response(request, false).await?;
+        let bytes = response.bytes().await?;
+
+        Ok(bytes.to_vec())
+    }
+
+    /// Generate speech, yielding audio bytes as they arrive.
+    /// Dropping the stream closes the connection, which is how callers cancel synthesis.
+    pub async fn text_to_speech_stream(
+        &self,
+        request: crate::ai::types::TextToSpeechRequest,
+    ) -> AIResult<Box<dyn Stream<Item = AIResult<Vec<u8>>> + Send + Unpin>> {
+        let response = self.speech_response(request, true).await?;
+        let stream = response
+            .bytes_stream()
+            .map(|chunk| chunk.map(|bytes| bytes.to_vec()).map_err(AIError::from));
+
+        Ok(Box::new(Box::pin(stream)))
+    }
+
+    /// Generate images from a prompt
+    pub async fn generate_image(
+        &self,
+        request: crate::ai::types::ImageGenerationRequest,
+    ) -> AIResult<crate::ai::types::ImageGenerationResponse> {
+        let base_url_string = self.get_base_url();
+        let base_url = base_url_string.trim_end_matches('/');
+        let url = format!("{}/images/generations", base_url);
+
+        // Build request body - only include non-None fields
+        let actual_model = extract_model_id(&request.model);
+        let mut body = serde_json::json!({
+            "model": actual_model,
+            "prompt": request.prompt,
+        });
+
+        if let Some(size) = request.size {
+            body["size"] = serde_json::json!(size);
+        }
+        if let Some(quality) = request.quality {
+            body["quality"] = serde_json::json!(quality);
+        }
+        if let Some(n) = request.n {
+            body["n"] = serde_json::json!(n);
+        }
+        if let Some(response_format) = request.response_format {
+            body["response_format"] = serde_json::json!(response_format);
+        }
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .headers(self.extra_headers.clone())
+            .json(&body)
+            .timeout(self.request_timeout)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(AIError::from_response("OpenAI image generation error", response).await);
+        }
+
+        let response_text = response.text().await?;
+        Ok(serde_json::from_str(&response_text)?)
+    }
+
+    /// Send a /audio/speech request and check the status, leaving the body unread.
+    /// Streamed responses are only bounded by the client's idle timeout.
+    async fn speech_response(
+        &self,
+        request: crate::ai::types::TextToSpeechRequest,
+        streaming: bool,
+    ) -> AIResult<reqwest::Response> {
         let base_url_string = self.get_base_url();
         let base_url = base_url_string.trim_end_matches('/');
         let url = format!("{}/audio/speech", base_url);
@@ -381,29 +800,116 @@ impl OpenAIProvider {
             body["response_format"] = serde_json::json!(response_format);
         }
 
-        let response = self
+        let mut builder = self
             .client
             .post(&url)
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
+            .headers(self.extra_headers.clone())
+            .json(&body);
+        if !streaming {
+            builder = builder.timeout(self.request_timeout);
+        }
+        let response = builder.send().await?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(AIError::ProviderError(format!(
-                "OpenAI TTS error ({}): {}",
-                status, error_text
-            )));
+            return Err(AIError::from_response("OpenAI TTS error", response).await);
         }
 
-        let bytes = response.bytes().await?;
+        Ok(response)
+    }
+}
 
-        Ok(bytes.to_vec())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::types::{ChatMessage, FunctionCall, MessageContent, ResponseFormat, Tool, ToolCall, ToolFunction};
+
+    fn message(role: Role, text: &str) -> ChatMessage {
+        ChatMessage {
+            role,
+            content: MessageContent::Text(text.to_string()),
+            name: None,
+            tool_call_id: None,
+            tool_calls: None,
+            audio: None,
+        }
+    }
+
+    /// The request `run_tool_loop` sends on its second call: the model asked for a tool and its
+    /// result was appended
+    fn second_tool_round(tools: Option<Vec<Tool>>) -> ChatCompletionRequest {
+        let assistant = ChatMessage {
+            tool_calls: Some(vec![ToolCall {
+                id: "call_1".to_string(),
+                tool_type: "function".to_string(),
+                function: FunctionCall {
+                    name: "get_time".to_string(),
+                    arguments: "{}".to_string(),
+                },
+            }]),
+            ..message(Role::Assistant, "")
+        };
+        let result = ChatMessage {
+            tool_call_id: Some("call_1".to_string()),
+            ..message(Role::Tool, "12:00")
+        };
+        ChatCompletionRequest {
+            model: "openai::gpt-4o".to_string(),
+            messages: vec![message(Role::User, "What time is it?"), assistant, result],
+            tools,
+            ..Default::default()
+        }
+    }
+
+    fn get_time_tool() -> Tool {
+        Tool {
+            tool_type: "function".to_string(),
+            function: ToolFunction {
+                name: "get_time".to_string(),
+                description: None,
+                parameters: serde_json::json!({"type": "object", "properties": {}}),
+            },
+        }
+    }
+
+    #[test]
+    fn chat_body_forwards_tool_round_with_tools() {
+        let body = chat_body(second_tool_round(Some(vec![get_time_tool()])), false);
+        let messages = body["messages"].as_array().unwrap();
+
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1]["tool_calls"][0]["id"], "call_1");
+        assert_eq!(messages[1]["tool_calls"][0]["function"]["name"], "get_time");
+        assert_eq!(messages[2]["role"], "tool");
+        assert_eq!(messages[2]["tool_call_id"], "call_1");
+        assert_eq!(messages[2]["content"], "12:00");
+        assert_eq!(body["tools"][0]["function"]["name"], "get_time");
+    }
+
+    #[test]
+    fn streamed_chat_body_keeps_response_format() {
+        let request = ChatCompletionRequest {
+            model: "gpt-4o".to_string(),
+            messages: vec![message(Role::User, "List three colors as JSON")],
+            response_format: Some(ResponseFormat {
+                format_type: "json_object".to_string(),
+                json_schema: None,
+            }),
+            ..Default::default()
+        };
+        let body = chat_body(request, true);
+
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["response_format"]["type"], "json_object");
+    }
+
+    #[test]
+    fn chat_body_drops_tool_history_without_tools() {
+        let body = chat_body(second_tool_round(None), false);
+        let messages = body["messages"].as_array().unwrap();
+
+        assert_eq!(messages.len(), 2);
+        assert!(messages[1].get("tool_calls").is_none());
     }
 }
@@ -1,26 +1,162 @@
-use futures::Stream;
+use futures::{Stream, StreamExt};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use crate::ai::audit;
+use crate::ai::circuit_breaker::CircuitBreaker;
 use crate::ai::error::{AIError, AIResult};
 use crate::ai::provider::AIProvider;
+use crate::ai::mcp::{McpClient, McpToolHandler};
+use crate::ai::rate_limiter::{RateLimitStatus, RateLimiter};
+use crate::ai::response_cache::{ResponseCache, ResponseCacheSettings};
+use crate::ai::retry::RetryConfig;
+use crate::ai::tools::{builtin_tools, RegisteredTool, ToolLoopResponse, DEFAULT_MAX_TOOL_ITERATIONS};
 use crate::ai::types::{
-    ChatCompletionRequest, ChatCompletionResponse, ProviderCredentials, Tool, StreamChunk,
+    ChatCompletionRequest, ChatCompletionResponse, ChatMessage, MessageContent, ProviderCredentials, ProviderFallback, RequestDefaults,
+    Role, Tool, StreamChunk, extract_model_id,
     AudioTranscriptionRequest, AudioTranscriptionResponse,
-    TextToSpeechRequest,
+    ImageGenerationRequest, ImageGenerationResponse, RealtimeTranscriptionEvent, TextToSpeechRequest,
 };
-use crate::ai::providers::OpenAIProvider;
+use crate::ai::providers::{openai, AnthropicProvider, AzureOpenAIProvider, OpenAIProvider};
 
 /// Main AI proxy orchestrator
 /// Stateless - credentials are passed per-request
 pub struct AIProxy {
     mcp_tools: Arc<RwLock<Vec<Tool>>>,
+    /// Connected MCP server; `mcp_tools` caches its tool schemas until the next (re)connect
+    mcp_client: RwLock<Option<Arc<McpClient>>>,
+    /// Tools executed by the backend in `chat_completion_with_tools`, keyed by function name
+    tool_handlers: std::sync::RwLock<HashMap<String, RegisteredTool>>,
+    request_defaults: std::sync::RwLock<RequestDefaults>,
+    circuit_breaker: CircuitBreaker,
+    rate_limiter: RateLimiter,
+    retry_config: RetryConfig,
+    response_cache: ResponseCache,
 }
 
 impl AIProxy {
-    pub fn new() -> Self {
-        Self {
+    pub fn new(retry_config: RetryConfig) -> Self {
+        let proxy = Self {
             mcp_tools: Arc::new(RwLock::new(Vec::new())),
+            mcp_client: RwLock::new(None),
+            tool_handlers: std::sync::RwLock::new(HashMap::new()),
+            request_defaults: std::sync::RwLock::new(RequestDefaults::default()),
+            circuit_breaker: CircuitBreaker::new(),
+            rate_limiter: RateLimiter::new(),
+            retry_config,
+            response_cache: ResponseCache::new(),
+        };
+        for tool in builtin_tools() {
+            proxy.register_tool(tool);
         }
+        proxy
+    }
+
+    /// Register a backend tool handler, replacing any existing one with the same function name
+    pub fn register_tool(&self, tool: RegisteredTool) {
+        let name = tool.definition.function.name.clone();
+        match self.tool_handlers.write() {
+            Ok(mut handlers) => handlers.insert(name, tool),
+            Err(poisoned) => poisoned.into_inner().insert(name, tool),
+        };
+    }
+
+    fn registered_tools(&self) -> Vec<RegisteredTool> {
+        match self.tool_handlers.read() {
+            Ok(handlers) => handlers.values().cloned().collect(),
+            Err(poisoned) => poisoned.into_inner().values().cloned().collect(),
+        }
+    }
+
+    /// Get the defaults merged into chat requests
+    pub fn get_request_defaults(&self) -> RequestDefaults {
+        match self.request_defaults.read() {
+            Ok(defaults) => defaults.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Replace the defaults merged into chat requests
+    pub fn set_request_defaults(&self, defaults: RequestDefaults) {
+        match self.request_defaults.write() {
+            Ok(mut current) => *current = defaults,
+            Err(poisoned) => *poisoned.into_inner() = defaults,
+        }
+    }
+
+    /// Configure the cache for deterministic chat completions, stored in `app_data_dir`
+    pub fn set_response_cache(&self, settings: ResponseCacheSettings, app_data_dir: &std::path::Path) {
+        self.response_cache.configure(settings, app_data_dir);
+    }
+
+    /// Throttle every base_url to `requests_per_minute` (None turns throttling off)
+    pub fn set_rate_limit(&self, requests_per_minute: Option<u32>) {
+        self.rate_limiter.set_rate(requests_per_minute);
+    }
+
+    pub fn rate_limit_status(&self) -> RateLimitStatus {
+        self.rate_limiter.status()
+    }
+
+    /// Run a provider call through the per-(base_url, api_key) rate limiter and per-base_url circuit
+    /// breaker, retrying transient failures (429/5xx) with backoff. `call` is invoked once per attempt.
+    async fn guarded<T, F, Fut>(&self, base_url: &str, api_key: &str, call: F) -> AIResult<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = AIResult<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            self.rate_limiter.acquire(base_url, api_key).await;
+            self.circuit_breaker.check(base_url)?;
+            let result = call().await;
+            self.circuit_breaker.record(base_url, &result);
+
+            let delay = match &result {
+                Err(error) => self.retry_config.delay_for(error, attempt),
+                Ok(_) => None,
+            };
+            let Some(delay) = delay else {
+                return result;
+            };
+
+            eprintln!(
+                "[AIProxy] Attempt {}/{} failed ({}), retrying in {}ms",
+                attempt,
+                self.retry_config.max_attempts,
+                result.as_ref().err().map(|e| e.to_string()).unwrap_or_default(),
+                delay.as_millis()
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Pick the chat provider implementation from `credentials.provider_kind`
+    fn create_chat_provider(credentials: ProviderCredentials) -> AIResult<Box<dyn AIProvider>> {
+        match credentials.provider_kind.as_str() {
+            "anthropic" => Ok(Box::new(AnthropicProvider::from_credentials(credentials)?)),
+            "azure" => Ok(Box::new(AzureOpenAIProvider::from_credentials(credentials)?)),
+            _ => Ok(Box::new(OpenAIProvider::from_credentials(credentials)?)),
+        }
+    }
+
+    /// Audio endpoints only exist on OpenAI-compatible providers
+    fn create_audio_provider(credentials: ProviderCredentials) -> AIResult<OpenAIProvider> {
+        match credentials.provider_kind.as_str() {
+            "anthropic" => {
+                return Err(AIError::ProviderError(
+                    "Anthropic does not provide audio transcription or speech endpoints".to_string(),
+                ));
+            }
+            "azure" => {
+                return Err(AIError::ProviderError(
+                    "Audio endpoints are not supported for Azure OpenAI deployments".to_string(),
+                ));
+            }
+            _ => {}
+        }
+        OpenAIProvider::from_credentials(credentials)
     }
 
     /// Get all MCP tools
@@ -29,23 +165,218 @@ impl AIProxy {
         mcp_tools.clone()
     }
 
-    /// Main chat completion method - credentials passed per-request
+    /// Connect to an MCP server (replacing any current connection) and cache its tools
+    pub async fn connect_mcp(&self, server_url: &str) -> AIResult<Vec<Tool>> {
+        self.disconnect_mcp().await;
+
+        let client = McpClient::connect(server_url).await.map_err(AIError::ProviderError)?;
+        let tools = client.list_tools().await.map_err(AIError::ProviderError)?;
+
+        *self.mcp_client.write().await = Some(Arc::new(client));
+        *self.mcp_tools.write().await = tools.clone();
+        Ok(tools)
+    }
+
+    /// Drop the MCP connection (stopping a stdio server) and its cached tools
+    pub async fn disconnect_mcp(&self) {
+        self.mcp_client.write().await.take();
+        self.mcp_tools.write().await.clear();
+    }
+
+    /// Handlers that execute the cached MCP tools on the connected server
+    async fn mcp_handlers(&self) -> Vec<RegisteredTool> {
+        let Some(client) = self.mcp_client.read().await.clone() else {
+            return Vec::new();
+        };
+        self.get_mcp_tools()
+            .await
+            .into_iter()
+            .map(|definition| RegisteredTool {
+                handler: Arc::new(McpToolHandler {
+                    client: Arc::clone(&client),
+                    name: definition.function.name.clone(),
+                }),
+                definition,
+            })
+            .collect()
+    }
+
+    /// A final `assistant` message is treated as an output prefill: it is forwarded unchanged
+    /// (not stripped or re-roled) so the model continues from its text, e.g. starting with `{`.
+    /// Anthropic continues from a prefill natively; OpenAI-compatible providers differ - some
+    /// continue it, others treat it as history and answer afresh. Only one is allowed.
+    fn validate_assistant_prefill(request: &ChatCompletionRequest) -> AIResult<()> {
+        let trailing_assistant = request
+            .messages
+            .iter()
+            .rev()
+            .take_while(|msg| matches!(msg.role, Role::Assistant))
+            .count();
+
+        if trailing_assistant > 1 {
+            return Err(AIError::ProviderError(
+                "Only one trailing assistant prefill message is allowed".to_string(),
+            ));
+        }
+
+        if let Some(last) = request.messages.last() {
+            if matches!(last.role, Role::Assistant) && last.tool_calls.is_some() {
+                return Err(AIError::ProviderError(
+                    "Assistant prefill message cannot contain tool calls".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// A `json_schema` response format needs a named object schema, and is rejected up front
+    /// for models known not to support it, which would otherwise fail with a generic 400
+    fn validate_response_format(request: &ChatCompletionRequest) -> AIResult<()> {
+        let Some(format) = request.response_format.as_ref().filter(|format| format.format_type == "json_schema") else {
+            return Ok(());
+        };
+        let Some(json_schema) = &format.json_schema else {
+            return Err(AIError::ProviderError(
+                "A json_schema response format needs a json_schema object".to_string(),
+            ));
+        };
+
+        let name = &json_schema.name;
+        if name.is_empty()
+            || name.len() > 64
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(AIError::ProviderError(format!(
+                "Invalid schema name '{}': use 1-64 letters, digits, underscores or dashes",
+                name
+            )));
+        }
+        if !json_schema.schema.is_object() {
+            return Err(AIError::ProviderError("The JSON schema must be an object".to_string()));
+        }
+
+        let model = extract_model_id(&request.model);
+        if openai::supports_json_schema(model) == Some(false) {
+            return Err(AIError::ProviderError(format!(
+                "Model {} does not support json_schema response formats; use json_object instead",
+                model
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Main chat completion method - credentials passed per-request.
+    /// Tool calls for a connected MCP server are executed on the server and fed back to the
+    /// model; any other tool calls are returned to the caller.
     pub async fn chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+        credentials: ProviderCredentials,
+    ) -> AIResult<ChatCompletionResponse> {
+        let mcp_handlers = self.mcp_handlers().await;
+        if mcp_handlers.is_empty() {
+            return self.complete_once(request, credentials).await;
+        }
+        self.run_tool_loop(request, credentials, mcp_handlers, DEFAULT_MAX_TOOL_ITERATIONS)
+            .await
+            .map(|result| result.response)
+    }
+
+    /// `chat_completion` over a provider chain: `credentials` first, then each fallback in order
+    /// for as long as the previous provider fails in a way another one might not (see
+    /// `AIError::is_failover`). Model ids keep working across providers because the
+    /// composite prefix is stripped; a fallback can also name its own model.
+    pub async fn chat_completion_with_fallbacks(
+        &self,
+        request: ChatCompletionRequest,
+        credentials: ProviderCredentials,
+        fallbacks: Vec<ProviderFallback>,
+    ) -> AIResult<ChatCompletionResponse> {
+        let chain = provider_chain(credentials, fallbacks);
+        let mut index = 0;
+        loop {
+            let (request, credentials) = chain_request(&request, &chain[index]);
+            match self.chat_completion(request, credentials).await {
+                Ok(response) => {
+                    return Ok(ChatCompletionResponse {
+                        provider_index: Some(index),
+                        ..response
+                    });
+                }
+                Err(e) if e.is_failover() && index + 1 < chain.len() => {
+                    eprintln!("[AIProxy] Provider {} failed ({}), falling back to provider {}", index, e, index + 1);
+                    index += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// A single model call
+    async fn complete_once(
         &self,
         mut request: ChatCompletionRequest,
         credentials: ProviderCredentials,
     ) -> AIResult<ChatCompletionResponse> {
+        Self::validate_assistant_prefill(&request)?;
+        Self::validate_response_format(&request)?;
+
+        // Fill fields the caller left unset from persisted defaults
+        self.get_request_defaults().apply_to(&mut request);
+        let audit_summary = audit::request_summary(&request, &credentials);
+
         // Create provider from credentials
-        let provider = OpenAIProvider::from_credentials(credentials)?;
+        let base_url = credentials.base_url.clone();
+        let api_key = credentials.api_key.clone();
+        let provider = Self::create_chat_provider(credentials)?;
 
         // Add MCP tools to request if available
         let mcp_tools = self.get_mcp_tools().await;
-        if !mcp_tools.is_empty() && provider.supports_tools() {
-            request.tools = Some(mcp_tools);
+        if provider.supports_tools() {
+            merge_tools(&mut request, mcp_tools);
         }
 
+        let cache_key = self.response_cache.key_for(&request, &base_url);
+        let cached = cache_key.as_deref().and_then(|key| self.response_cache.get(key));
+
         // Execute completion
-        provider.chat_completion(request).await
+        let started = std::time::Instant::now();
+        let result = match cached {
+            Some(response) => Ok(ChatCompletionResponse { cached: true, ..response }),
+            None => self.guarded(&base_url, &api_key, || provider.chat_completion(request.clone())).await,
+        };
+        if let (Some(key), Ok(response)) = (cache_key, &result) {
+            if !response.cached {
+                self.response_cache.insert(key, response);
+            }
+        }
+
+        if let Some(summary) = audit_summary {
+            let outcome = match &result {
+                Ok(response) => serde_json::json!({
+                    "status": if response.cached { "cached" } else { "ok" },
+                    "model": response.model,
+                    "finish_reason": response.choices.first().and_then(|choice| choice.finish_reason.clone()),
+                    "usage": response.usage,
+                    "duration_ms": started.elapsed().as_millis() as u64,
+                }),
+                Err(e) => audit::error_outcome(e, started.elapsed()),
+            };
+            audit::record("chat_completion", summary, outcome);
+        }
+
+        result
+    }
+
+    /// Whether a stream for `request` from this provider asks for JSON output, so its content can
+    /// be repaired as JSON. Anthropic has no JSON mode; its streams are free text.
+    pub fn stream_requests_json(request: &ChatCompletionRequest, credentials: &ProviderCredentials) -> bool {
+        credentials.provider_kind != "anthropic"
+            && openai::chat_body(request.clone(), true)["response_format"]["type"]
+                .as_str()
+                .is_some_and(|format_type| format_type.starts_with("json"))
     }
 
     /// Chat completion with streaming - credentials passed per-request
@@ -54,22 +385,134 @@ impl AIProxy {
         mut request: ChatCompletionRequest,
         credentials: ProviderCredentials,
     ) -> AIResult<Box<dyn Stream<Item = AIResult<StreamChunk>> + Send + Unpin>> {
+        Self::validate_assistant_prefill(&request)?;
+        Self::validate_response_format(&request)?;
+
         // Create provider from credentials
-        let provider = OpenAIProvider::from_credentials(credentials)?;
+        let base_url = credentials.base_url.clone();
+        let api_key = credentials.api_key.clone();
+        let provider = Self::create_chat_provider(credentials)?;
 
         // Check if provider supports streaming
         if !provider.supports_streaming() {
             return Err(AIError::ProviderError("Provider does not support streaming".to_string()));
         }
 
+        // Fill fields the caller left unset from persisted defaults
+        self.get_request_defaults().apply_to(&mut request);
+
         // Add MCP tools to request if available
         let mcp_tools = self.get_mcp_tools().await;
-        if !mcp_tools.is_empty() && provider.supports_tools() {
-            request.tools = Some(mcp_tools);
+        if provider.supports_tools() {
+            merge_tools(&mut request, mcp_tools);
         }
 
-        // Execute streaming completion
-        provider.chat_completion_stream(request).await
+        // Execute streaming completion (only the connection is tracked by the breaker)
+        self.guarded(&base_url, &api_key, || provider.chat_completion_stream(request.clone())).await
+    }
+
+    /// Streaming counterpart of `chat_completion_with_fallbacks`. A provider is only abandoned
+    /// before anything was received: the first chunk is awaited here, and once it arrives the
+    /// stream is committed, since a partially delivered answer can't be replayed elsewhere.
+    /// Returns the stream and the index of the provider serving it (0 = primary).
+    pub async fn chat_completion_stream_with_fallbacks(
+        &self,
+        request: ChatCompletionRequest,
+        credentials: ProviderCredentials,
+        fallbacks: Vec<ProviderFallback>,
+    ) -> AIResult<(Box<dyn Stream<Item = AIResult<StreamChunk>> + Send + Unpin>, usize)> {
+        let chain = provider_chain(credentials, fallbacks);
+        let mut index = 0;
+        loop {
+            let (request, credentials) = chain_request(&request, &chain[index]);
+            let error = match self.chat_completion_stream(request, credentials).await {
+                Ok(mut stream) => match stream.next().await {
+                    Some(Err(e)) => e,
+                    first => {
+                        let stream: Box<dyn Stream<Item = AIResult<StreamChunk>> + Send + Unpin> =
+                            Box::new(futures::stream::iter(first).chain(stream));
+                        return Ok((stream, index));
+                    }
+                },
+                Err(e) => e,
+            };
+            if !error.is_failover() || index + 1 == chain.len() {
+                return Err(error);
+            }
+            eprintln!("[AIProxy] Provider {} failed ({}), falling back to provider {}", index, error, index + 1);
+            index += 1;
+        }
+    }
+
+    /// Chat completion that executes tool calls on the backend: whenever the model stops with
+    /// `finish_reason == "tool_calls"`, the registered handlers (and connected MCP tools) run,
+    /// their results are appended as `tool` messages, and the model is called again.
+    pub async fn chat_completion_with_tools(
+        &self,
+        request: ChatCompletionRequest,
+        credentials: ProviderCredentials,
+        max_iterations: u32,
+    ) -> AIResult<ToolLoopResponse> {
+        let mut handlers = self.registered_tools();
+        handlers.extend(self.mcp_handlers().await);
+        self.run_tool_loop(request, credentials, handlers, max_iterations).await
+    }
+
+    /// Call the model until it stops requesting tools. Stops when the model answers, when it calls
+    /// a tool with no handler in `registered` (returned to the caller as-is), or after `max_iterations` calls.
+    async fn run_tool_loop(
+        &self,
+        mut request: ChatCompletionRequest,
+        credentials: ProviderCredentials,
+        registered: Vec<RegisteredTool>,
+        max_iterations: u32,
+    ) -> AIResult<ToolLoopResponse> {
+        merge_tools(&mut request, registered.iter().map(|tool| tool.definition.clone()).collect());
+
+        let mut messages = Vec::new();
+        for iteration in 1..=max_iterations.max(1) {
+            let response = self.complete_once(request.clone(), credentials.clone()).await?;
+
+            let tool_calls = match response.choices.first() {
+                Some(choice) if choice.finish_reason.as_deref() == Some("tool_calls") => {
+                    choice.message.tool_calls.clone().unwrap_or_default()
+                }
+                _ => Vec::new(),
+            };
+            let handled = !tool_calls.is_empty()
+                && tool_calls.iter().all(|call| {
+                    registered.iter().any(|tool| tool.definition.function.name == call.function.name)
+                });
+            if !handled {
+                return Ok(ToolLoopResponse { response, messages, iterations: iteration });
+            }
+
+            let assistant = response.choices[0].message.clone();
+            request.messages.push(assistant.clone());
+            messages.push(assistant);
+
+            for call in tool_calls {
+                let Some(tool) = registered.iter().find(|tool| tool.definition.function.name == call.function.name) else {
+                    continue;
+                };
+                let arguments = serde_json::from_str(&call.function.arguments)
+                    .unwrap_or_else(|_| serde_json::json!({}));
+                // Handler failures are reported to the model so it can recover or explain
+                let output = match tool.handler.call(arguments).await {
+                    Ok(output) => output,
+                    Err(e) => format!("Error: {}", e),
+                };
+
+                let result = tool_result_message(call.id, output);
+                request.messages.push(result.clone());
+                messages.push(result);
+            }
+        }
+
+        Err(AIError::ProviderError(format!(
+            "Model was still requesting tools after {} iterations",
+            max_iterations.max(1)
+        )))
     }
 
     /// Transcribe audio - credentials passed per-request
@@ -79,8 +522,59 @@ impl AIProxy {
         request: AudioTranscriptionRequest,
         credentials: ProviderCredentials,
     ) -> AIResult<AudioTranscriptionResponse> {
+        let base_url = credentials.base_url.clone();
+        let api_key = credentials.api_key.clone();
+        let provider = Self::create_audio_provider(credentials)?;
+        self.guarded(&base_url, &api_key, || provider.transcribe_audio(audio_data.clone(), request.clone())).await
+    }
+
+    /// Transcribe audio as a stream of text deltas - credentials passed per-request
+    pub async fn transcribe_audio_stream(
+        &self,
+        audio_data: Vec<u8>,
+        request: AudioTranscriptionRequest,
+        credentials: ProviderCredentials,
+    ) -> AIResult<Box<dyn Stream<Item = AIResult<String>> + Send + Unpin>> {
+        let base_url = credentials.base_url.clone();
+        let api_key = credentials.api_key.clone();
+        let provider = Self::create_audio_provider(credentials)?;
+        self.guarded(&base_url, &api_key, || provider.transcribe_audio_stream(audio_data.clone(), request.clone()))
+            .await
+    }
+
+    /// Realtime transcription of audio fed through `audio` (see `OpenAIProvider::transcribe_realtime`).
+    /// Not retried: audio already sent can't be replayed into a new session.
+    pub async fn transcribe_realtime(
+        &self,
+        request: AudioTranscriptionRequest,
+        credentials: ProviderCredentials,
+        audio: tokio::sync::mpsc::Receiver<Vec<i16>>,
+    ) -> AIResult<Box<dyn Stream<Item = AIResult<RealtimeTranscriptionEvent>> + Send + Unpin>> {
+        let provider = Self::create_audio_provider(credentials)?;
+        provider.transcribe_realtime(request, audio).await
+    }
+
+    /// Generate images - credentials passed per-request
+    pub async fn generate_image(
+        &self,
+        request: ImageGenerationRequest,
+        credentials: ProviderCredentials,
+    ) -> AIResult<ImageGenerationResponse> {
+        match credentials.provider_kind.as_str() {
+            "anthropic" => {
+                return Err(AIError::ProviderError("Anthropic does not provide image generation".to_string()));
+            }
+            "azure" => {
+                return Err(AIError::ProviderError(
+                    "Image generation is not supported for Azure OpenAI deployments".to_string(),
+                ));
+            }
+            _ => {}
+        }
+        let base_url = credentials.base_url.clone();
+        let api_key = credentials.api_key.clone();
         let provider = OpenAIProvider::from_credentials(credentials)?;
-        provider.transcribe_audio(audio_data, request).await
+        self.guarded(&base_url, &api_key, || provider.generate_image(request.clone())).await
     }
 
     /// Generate speech from text - credentials passed per-request
@@ -89,13 +583,188 @@ impl AIProxy {
         request: TextToSpeechRequest,
         credentials: ProviderCredentials,
     ) -> AIResult<Vec<u8>> {
-        let provider = OpenAIProvider::from_credentials(credentials)?;
-        provider.text_to_speech(request).await
+        let base_url = credentials.base_url.clone();
+        let api_key = credentials.api_key.clone();
+        let provider = Self::create_audio_provider(credentials)?;
+        self.guarded(&base_url, &api_key, || provider.text_to_speech(request.clone())).await
+    }
+
+    /// Generate speech as a stream of audio chunks - credentials passed per-request
+    pub async fn text_to_speech_stream(
+        &self,
+        request: TextToSpeechRequest,
+        credentials: ProviderCredentials,
+    ) -> AIResult<Box<dyn Stream<Item = AIResult<Vec<u8>>> + Send + Unpin>> {
+        let base_url = credentials.base_url.clone();
+        let api_key = credentials.api_key.clone();
+        let provider = Self::create_audio_provider(credentials)?;
+        self.guarded(&base_url, &api_key, || provider.text_to_speech_stream(request.clone())).await
+    }
+}
+
+/// `tool` message answering the tool call `call_id`
+fn tool_result_message(call_id: String, output: String) -> ChatMessage {
+    ChatMessage {
+        role: Role::Tool,
+        content: MessageContent::Text(output),
+        name: None,
+        tool_call_id: Some(call_id),
+        tool_calls: None,
+        audio: None,
     }
 }
 
+/// Add `tools` to the request, keeping caller-supplied definitions when names collide
+fn merge_tools(request: &mut ChatCompletionRequest, tools: Vec<Tool>) {
+    if tools.is_empty() {
+        return;
+    }
+    let existing = request.tools.get_or_insert_with(Vec::new);
+    for tool in tools {
+        if !existing.iter().any(|t| t.function.name == tool.function.name) {
+            existing.push(tool);
+        }
+    }
+}
+
+/// The primary provider followed by its fallbacks
+fn provider_chain(credentials: ProviderCredentials, fallbacks: Vec<ProviderFallback>) -> Vec<ProviderFallback> {
+    std::iter::once(ProviderFallback { credentials, model: None })
+        .chain(fallbacks)
+        .collect()
+}
+
+/// Request and credentials for one provider in a chain
+fn chain_request(request: &ChatCompletionRequest, provider: &ProviderFallback) -> (ChatCompletionRequest, ProviderCredentials) {
+    let mut request = request.clone();
+    if let Some(model) = &provider.model {
+        request.model = model.clone();
+    }
+    (request, provider.credentials.clone())
+}
+
 impl Default for AIProxy {
     fn default() -> Self {
-        Self::new()
+        Self::new(RetryConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::types::{FunctionCall, ToolCall, ToolFunction};
+
+    /// An MCP tool as `mcp_handlers` registers it
+    fn mcp_tool(name: &str) -> Tool {
+        Tool {
+            tool_type: "function".to_string(),
+            function: ToolFunction {
+                name: name.to_string(),
+                description: Some("Search the docs".to_string()),
+                parameters: serde_json::json!({"type": "object", "properties": {"query": {"type": "string"}}}),
+            },
+        }
+    }
+
+    fn credentials(provider_kind: &str) -> ProviderCredentials {
+        serde_json::from_value(serde_json::json!({
+            "api_key": "key",
+            "base_url": "https://api.example.com/v1",
+            "provider_kind": provider_kind,
+        }))
+        .unwrap()
+    }
+
+    fn json_request() -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: "gpt-4o".to_string(),
+            response_format: Some(crate::ai::types::ResponseFormat {
+                format_type: "json_object".to_string(),
+                json_schema: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn only_streams_that_send_response_format_are_json() {
+        assert!(AIProxy::stream_requests_json(&json_request(), &credentials("")));
+        assert!(AIProxy::stream_requests_json(&json_request(), &credentials("azure")));
+        assert!(!AIProxy::stream_requests_json(&json_request(), &credentials("anthropic")));
+        let text_request = ChatCompletionRequest {
+            response_format: None,
+            ..json_request()
+        };
+        assert!(!AIProxy::stream_requests_json(&text_request, &credentials("")));
+    }
+
+    #[tokio::test]
+    async fn truncated_json_mode_stream_is_repaired() {
+        // A JSON-mode stream as OpenAI sends it, cut off mid-string by an abort
+        let body = [
+            r#"data: {"id":"c1","object":"chat.completion.chunk","created":1,"model":"gpt-4o","choices":[{"index":0,"delta":{"role":"assistant","content":""},"finish_reason":null}]}"#,
+            r#"data: {"id":"c1","object":"chat.completion.chunk","created":1,"model":"gpt-4o","choices":[{"index":0,"delta":{"content":"{\"colors\": [\"red\", "},"finish_reason":null}]}"#,
+            r#"data: {"id":"c1","object":"chat.completion.chunk","created":1,"model":"gpt-4o","choices":[{"index":0,"delta":{"content":"\"gre"},"finish_reason":null}]}"#,
+        ]
+        .iter()
+        .map(|event| Ok::<_, std::io::Error>(format!("{}\n\n", event).into_bytes()))
+        .collect::<Vec<_>>();
+
+        let mut stream = openai::sse_chunk_stream(futures::stream::iter(body));
+        let mut content = String::new();
+        while let Some(chunk) = stream.next().await {
+            content.push_str(&chunk.unwrap().content);
+        }
+        assert_eq!(content, r#"{"colors": ["red", "gre"#);
+
+        let repaired = crate::ai::json_repair::repair_json(&content).unwrap();
+        assert!(repaired.repaired);
+        let value: serde_json::Value = serde_json::from_str(&repaired.text).unwrap();
+        assert_eq!(value["colors"][0], "red");
+    }
+
+    #[test]
+    fn mcp_tool_round_reaches_openai_body() {
+        // What chat_completion's MCP loop sends on its second call: the request had no tools of
+        // its own, the MCP tools were merged in, and the call and its result were appended
+        let mut request = ChatCompletionRequest {
+            model: "gpt-4o".to_string(),
+            messages: vec![ChatMessage {
+                role: Role::User,
+                content: MessageContent::Text("How do I configure the proxy?".to_string()),
+                name: None,
+                tool_call_id: None,
+                tool_calls: None,
+                audio: None,
+            }],
+            ..Default::default()
+        };
+        merge_tools(&mut request, vec![mcp_tool("search_docs")]);
+        request.messages.push(ChatMessage {
+            role: Role::Assistant,
+            content: MessageContent::Text(String::new()),
+            name: None,
+            tool_call_id: None,
+            tool_calls: Some(vec![ToolCall {
+                id: "call_mcp".to_string(),
+                tool_type: "function".to_string(),
+                function: FunctionCall {
+                    name: "search_docs".to_string(),
+                    arguments: r#"{"query":"proxy"}"#.to_string(),
+                },
+            }]),
+            audio: None,
+        });
+        request.messages.push(tool_result_message("call_mcp".to_string(), "Set proxy_url in settings".to_string()));
+
+        // OpenAI and Azure both build their bodies with chat_body
+        let body = openai::chat_body(request, false);
+        let messages = body["messages"].as_array().unwrap();
+
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1]["tool_calls"][0]["function"]["name"], "search_docs");
+        assert_eq!(messages[2]["role"], "tool");
+        assert_eq!(messages[2]["tool_call_id"], "call_mcp");
+        assert_eq!(messages[2]["content"], "Set proxy_url in settings");
     }
 }
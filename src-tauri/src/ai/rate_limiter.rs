@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+struct Bucket {
+    /// Negative while callers are waiting for tokens they have already reserved
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+#[derive(Default)]
+struct LimiterState {
+    requests_per_minute: Option<u32>,
+    buckets: HashMap<BucketKey, Bucket>,
+}
+
+/// Buckets are per account: the same base_url with different API keys has separate limits
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct BucketKey {
+    base_url: String,
+    /// Hex SHA-256 of the API key, so the key itself is never kept or reported
+    key_hash: String,
+}
+
+impl BucketKey {
+    fn new(base_url: &str, api_key: &str) -> Self {
+        let digest = Sha256::digest(api_key.as_bytes());
+        Self {
+            base_url: base_url.to_string(),
+            key_hash: digest.iter().map(|byte| format!("{:02x}", byte)).collect(),
+        }
+    }
+}
+
+/// Token bucket state for one base_url and API key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitBucket {
+    pub base_url: String,
+    /// First 8 hex digits of the API key's SHA-256, to tell accounts on one base_url apart
+    pub key_id: String,
+    /// Requests that can start right now (whole tokens)
+    pub available: u32,
+    /// How long a request made now would wait
+    pub wait_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitStatus {
+    pub requests_per_minute: Option<u32>,
+    pub buckets: Vec<RateLimitBucket>,
+}
+
+/// Per-(base_url, API key) token bucket that delays requests over the configured rate instead of
+/// letting them hit the provider's 429s. Allows bursts of up to a minute's worth of requests;
+/// concurrent requests with the same base_url and key share one bucket and are served in order.
+#[derive(Default)]
+pub struct RateLimiter {
+    state: Mutex<LimiterState>,
+}
+
+impl Bucket {
+    fn refill(&mut self, requests_per_minute: u32) {
+        let capacity = requests_per_minute as f64;
+        let elapsed = self.refilled_at.elapsed().as_secs_f64();
+        self.tokens = (self.tokens + elapsed * capacity / 60.0).min(capacity);
+        self.refilled_at = Instant::now();
+    }
+
+    fn wait(&self, requests_per_minute: u32) -> Duration {
+        let missing = (1.0 - self.tokens).max(0.0);
+        Duration::from_secs_f64(missing * 60.0 / requests_per_minute as f64)
+    }
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LimiterState> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Limit every base_url and API key to `requests_per_minute`; None or 0 turns limiting off
+    pub fn set_rate(&self, requests_per_minute: Option<u32>) {
+        let mut state = self.lock();
+        state.requests_per_minute = requests_per_minute.filter(|rpm| *rpm > 0);
+        // Start from full buckets at the new rate
+        state.buckets.clear();
+    }
+
+    /// Take a token for `base_url` and `api_key`, waiting until one is available
+    pub async fn acquire(&self, base_url: &str, api_key: &str) {
+        let wait = {
+            let mut state = self.lock();
+            let Some(rpm) = state.requests_per_minute else {
+                return;
+            };
+            let bucket = state.buckets.entry(BucketKey::new(base_url, api_key)).or_insert_with(|| Bucket {
+                tokens: rpm as f64,
+                refilled_at: Instant::now(),
+            });
+            bucket.refill(rpm);
+            let wait = bucket.wait(rpm);
+            // Reserve the token now so later callers queue behind this one
+            bucket.tokens -= 1.0;
+            wait
+        };
+
+        if !wait.is_zero() {
+            eprintln!("[RateLimit] Delaying request to {} by {}ms", base_url, wait.as_millis());
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    pub fn status(&self) -> RateLimitStatus {
+        let mut state = self.lock();
+        let requests_per_minute = state.requests_per_minute;
+        let buckets = match requests_per_minute {
+            Some(rpm) => state
+                .buckets
+                .iter_mut()
+                .map(|(key, bucket)| {
+                    bucket.refill(rpm);
+                    RateLimitBucket {
+                        base_url: key.base_url.clone(),
+                        key_id: key.key_hash[..8].to_string(),
+                        available: bucket.tokens.max(0.0).floor() as u32,
+                        wait_ms: bucket.wait(rpm).as_millis() as u64,
+                    }
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        RateLimitStatus {
+            requests_per_minute,
+            buckets,
+        }
+    }
+}
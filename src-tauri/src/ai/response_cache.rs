@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use crate::ai::types::{ChatCompletionRequest, ChatCompletionResponse};
+
+/// JSON file in the app data dir that keeps cached responses across restarts
+const CACHE_FILE: &str = "response-cache.json";
+
+/// Cache of deterministic chat completions, off by default. Only requests with temperature 0
+/// and no tools are cached; the key covers the whole request (after defaults) and the endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResponseCacheSettings {
+    pub enabled: bool,
+    /// Least recently used responses are dropped beyond this
+    pub max_entries: usize,
+    /// Responses older than this are refetched
+    pub ttl_secs: u64,
+}
+
+impl Default for ResponseCacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_entries: 200,
+            ttl_secs: 24 * 60 * 60,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedResponse {
+    key: String,
+    /// Unix timestamp (seconds)
+    created_at: i64,
+    response: ChatCompletionResponse,
+}
+
+#[derive(Default)]
+struct CacheState {
+    settings: ResponseCacheSettings,
+    path: Option<PathBuf>,
+    /// Ordered from least to most recently used
+    entries: Vec<CachedResponse>,
+}
+
+#[derive(Default)]
+pub struct ResponseCache {
+    state: Mutex<CacheState>,
+}
+
+impl CacheState {
+    fn prune(&mut self) {
+        let now = chrono::Utc::now().timestamp();
+        let ttl = self.settings.ttl_secs as i64;
+        self.entries.retain(|entry| now - entry.created_at < ttl);
+        let excess = self.entries.len().saturating_sub(self.settings.max_entries);
+        self.entries.drain(..excess);
+    }
+
+    fn persist(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = serde_json::to_vec(&self.entries)
+            .map_err(|e| e.to_string())
+            .and_then(|json| {
+                // Write a temp file in the same directory and rename it over the cache, so a
+                // crash mid-write never leaves a truncated file behind
+                let temp_path = path.with_extension("tmp");
+                let mut file = std::fs::File::create(&temp_path).map_err(|e| e.to_string())?;
+                file.write_all(&json).map_err(|e| e.to_string())?;
+                file.sync_all().map_err(|e| e.to_string())?;
+                std::fs::rename(&temp_path, path).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            eprintln!("[ResponseCache] Failed to write {}: {}", path.display(), e);
+        }
+    }
+}
+
+impl ResponseCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CacheState> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Apply settings; responses are kept in `response-cache.json` in `app_data_dir`.
+    /// Disabling the cache deletes what it holds.
+    pub fn configure(&self, settings: ResponseCacheSettings, app_data_dir: &Path) {
+        let path = app_data_dir.join(CACHE_FILE);
+        let mut state = self.lock();
+
+        if !settings.enabled {
+            state.entries.clear();
+            if path.exists() {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    eprintln!("[ResponseCache] Failed to remove {}: {}", path.display(), e);
+                }
+            }
+            state.settings = settings;
+            state.path = Some(path);
+            return;
+        }
+
+        if state.path.is_none() || !state.settings.enabled {
+            state.entries = std::fs::read(&path)
+                .ok()
+                .and_then(|json| serde_json::from_slice(&json).ok())
+                .unwrap_or_default();
+        }
+        state.settings = settings;
+        state.path = Some(path);
+        state.prune();
+        state.persist();
+    }
+
+    /// Cache key for a request about to be sent to `base_url`, or None when caching is off or
+    /// the request isn't deterministic (temperature unset or above 0, or tools attached)
+    pub fn key_for(&self, request: &ChatCompletionRequest, base_url: &str) -> Option<String> {
+        if !self.lock().settings.enabled {
+            return None;
+        }
+        if request.temperature != Some(0.0) || request.tools.as_ref().is_some_and(|tools| !tools.is_empty()) {
+            return None;
+        }
+
+        // serde_json maps are sorted, so equal requests serialize identically
+        let normalized = serde_json::json!({
+            "base_url": base_url.trim_end_matches('/'),
+            "request": request,
+        });
+        let digest = Sha256::digest(normalized.to_string().as_bytes());
+        Some(digest.iter().map(|byte| format!("{:02x}", byte)).collect())
+    }
+
+    /// Cached response for `key`, marked most recently used
+    pub fn get(&self, key: &str) -> Option<ChatCompletionResponse> {
+        let mut state = self.lock();
+        state.prune();
+        let index = state.entries.iter().position(|entry| entry.key == key)?;
+        let entry = state.entries.remove(index);
+        let response = entry.response.clone();
+        state.entries.push(entry);
+        Some(response)
+    }
+
+    pub fn insert(&self, key: String, response: &ChatCompletionResponse) {
+        let mut state = self.lock();
+        if !state.settings.enabled {
+            return;
+        }
+        state.entries.retain(|entry| entry.key != key);
+        state.entries.push(CachedResponse {
+            key,
+            created_at: chrono::Utc::now().timestamp(),
+            response: response.clone(),
+        });
+        state.prune();
+        state.persist();
+    }
+}
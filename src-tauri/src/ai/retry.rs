@@ -0,0 +1,70 @@
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::ai::error::AIError;
+
+/// Statuses worth retrying: rate limits and transient upstream failures (529 = overloaded)
+const RETRYABLE_STATUSES: [u16; 5] = [429, 500, 502, 503, 529];
+
+/// Retry policy for transient provider failures
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Total attempts including the first one; 1 disables retries
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on each following one
+    pub base_delay_ms: u64,
+    /// Upper bound for a single backoff delay (also caps Retry-After)
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 30_000,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Delay before retrying `error` after `attempt` (1-based) failed attempts,
+    /// or None when the error is not retryable or attempts are exhausted
+    pub fn delay_for(&self, error: &AIError, attempt: u32) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        let AIError::ApiError { status, retry_after_secs, .. } = error else {
+            return None;
+        };
+        if !RETRYABLE_STATUSES.contains(status) {
+            return None;
+        }
+
+        let delay_ms = match retry_after_secs {
+            Some(secs) => secs.saturating_mul(1000),
+            None => {
+                let backoff = self
+                    .base_delay_ms
+                    .saturating_mul(1u64 << (attempt - 1).min(20));
+                // Equal jitter in [backoff/2, backoff] so concurrent clients spread out
+                backoff / 2 + jitter_ms(backoff / 2)
+            }
+        };
+
+        Some(Duration::from_millis(delay_ms.min(self.max_delay_ms)))
+    }
+}
+
+/// Pseudo-random value in [0, max] derived from the clock; good enough to de-synchronize retries
+fn jitter_ms(max: u64) -> u64 {
+    if max == 0 {
+        return 0;
+    }
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos() as u64;
+    nanos % (max + 1)
+}
@@ -0,0 +1,121 @@
+use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+use tiktoken_rs::CoreBPE;
+use crate::ai::types::{extract_model_id, ChatCompletionRequest, ContentPart, MessageContent};
+
+/// Per-message framing overhead (role and separators) in OpenAI chat formats
+const TOKENS_PER_MESSAGE: usize = 3;
+/// Extra token when a message carries a `name`
+const TOKENS_PER_NAME: usize = 1;
+/// Every reply is primed with `<|start|>assistant<|message|>`
+const REPLY_PRIMING_TOKENS: usize = 3;
+/// Low-detail images are a flat cost
+const LOW_DETAIL_IMAGE_TOKENS: usize = 85;
+/// Image dimensions aren't decoded, so other images are assumed to be a 1024x1024
+/// high-detail image: 85 base + 170 per 512px tile (4 tiles)
+const HIGH_DETAIL_IMAGE_TOKENS: usize = 85 + 170 * 4;
+
+/// Input price in USD per million tokens, matched by model id prefix (most specific first)
+const INPUT_PRICING: &[(&str, f64)] = &[
+    ("gpt-4o-mini", 0.15),
+    ("gpt-4o", 2.50),
+    ("gpt-4.1-nano", 0.10),
+    ("gpt-4.1-mini", 0.40),
+    ("gpt-4.1", 2.00),
+    ("gpt-4-turbo", 10.00),
+    ("gpt-4", 30.00),
+    ("gpt-3.5-turbo", 0.50),
+    ("o1-mini", 1.10),
+    ("o1", 15.00),
+    ("o3-mini", 1.10),
+    ("o3", 2.00),
+    ("o4-mini", 1.10),
+];
+
+/// Local token estimate for a chat request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenEstimate {
+    pub prompt_tokens: usize,
+    /// Tokenizer used for counting ("o200k_base" or "cl100k_base")
+    pub encoding: String,
+    /// Prompt cost in USD, when the model is in the pricing table
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_cost_usd: Option<f64>,
+}
+
+static CL100K: OnceLock<CoreBPE> = OnceLock::new();
+static O200K: OnceLock<CoreBPE> = OnceLock::new();
+
+/// Newer OpenAI models (gpt-4o, gpt-4.1, o-series) use o200k; older ones and unknown
+/// models fall back to cl100k, which is close enough for estimates on other providers
+fn uses_o200k(model: &str) -> bool {
+    ["gpt-4o", "gpt-4.1", "gpt-5", "o1", "o3", "o4"]
+        .iter()
+        .any(|prefix| model.starts_with(prefix))
+}
+
+fn encoder(o200k: bool) -> Result<&'static CoreBPE, String> {
+    let (cell, load): (&OnceLock<CoreBPE>, fn() -> anyhow::Result<CoreBPE>) = if o200k {
+        (&O200K, tiktoken_rs::o200k_base)
+    } else {
+        (&CL100K, tiktoken_rs::cl100k_base)
+    };
+
+    if let Some(bpe) = cell.get() {
+        return Ok(bpe);
+    }
+    let bpe = load().map_err(|e| format!("Failed to load tokenizer: {}", e))?;
+    Ok(cell.get_or_init(|| bpe))
+}
+
+fn input_price_per_million(model: &str) -> Option<f64> {
+    INPUT_PRICING
+        .iter()
+        .find(|(prefix, _)| model.starts_with(prefix))
+        .map(|(_, price)| *price)
+}
+
+/// Count prompt tokens for `request` as `model` would see them, without any network call
+pub fn estimate_tokens(request: &ChatCompletionRequest, model: &str) -> Result<TokenEstimate, String> {
+    let model = extract_model_id(model);
+    let o200k = uses_o200k(model);
+    let bpe = encoder(o200k)?;
+    let count = |text: &str| bpe.encode_with_special_tokens(text).len();
+
+    let mut tokens = REPLY_PRIMING_TOKENS;
+    for msg in &request.messages {
+        tokens += TOKENS_PER_MESSAGE;
+        match &msg.content {
+            MessageContent::Text(text) => tokens += count(text),
+            MessageContent::Parts(parts) => {
+                for part in parts {
+                    tokens += match part {
+                        ContentPart::Text { text } => count(text),
+                        ContentPart::ImageUrl { image_url } => match image_url.detail.as_deref() {
+                            Some("low") => LOW_DETAIL_IMAGE_TOKENS,
+                            _ => HIGH_DETAIL_IMAGE_TOKENS,
+                        },
+                    };
+                }
+            }
+        }
+        if let Some(name) = &msg.name {
+            tokens += count(name) + TOKENS_PER_NAME;
+        }
+        for call in msg.tool_calls.iter().flatten() {
+            tokens += count(&call.function.name) + count(&call.function.arguments);
+        }
+    }
+
+    // Tool definitions are injected into the prompt; their JSON is a reasonable proxy
+    if let Some(tools) = &request.tools {
+        let definitions = serde_json::to_string(tools).unwrap_or_default();
+        tokens += count(&definitions);
+    }
+
+    Ok(TokenEstimate {
+        prompt_tokens: tokens,
+        encoding: if o200k { "o200k_base" } else { "cl100k_base" }.to_string(),
+        estimated_cost_usd: input_price_per_million(model).map(|price| tokens as f64 * price / 1_000_000.0),
+    })
+}
@@ -0,0 +1,56 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use crate::ai::types::{ChatCompletionResponse, ChatMessage, Tool, ToolFunction};
+
+/// Default cap on model round-trips in a tool-calling loop
+pub const DEFAULT_MAX_TOOL_ITERATIONS: u32 = 8;
+
+/// Backend implementation of a function the model can call
+#[async_trait]
+pub trait ToolHandler: Send + Sync {
+    /// Run the tool with the model-supplied arguments; the returned text is sent back as the tool result
+    async fn call(&self, arguments: serde_json::Value) -> Result<String, String>;
+}
+
+/// A tool definition together with the handler that executes it
+#[derive(Clone)]
+pub struct RegisteredTool {
+    pub definition: Tool,
+    pub handler: Arc<dyn ToolHandler>,
+}
+
+/// Result of a tool-calling loop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolLoopResponse {
+    /// Last model response: either a final answer, or tool calls without a backend handler
+    pub response: ChatCompletionResponse,
+    /// Messages appended during the loop (assistant tool calls and tool results), in order
+    pub messages: Vec<ChatMessage>,
+    /// Number of model calls made
+    pub iterations: u32,
+}
+
+/// Tools available without any external server
+pub fn builtin_tools() -> Vec<RegisteredTool> {
+    vec![RegisteredTool {
+        definition: Tool {
+            tool_type: "function".to_string(),
+            function: ToolFunction {
+                name: "get_current_time".to_string(),
+                description: Some("Get the current local date and time (RFC 3339, with UTC offset)".to_string()),
+                parameters: serde_json::json!({ "type": "object", "properties": {} }),
+            },
+        },
+        handler: Arc::new(CurrentTimeTool),
+    }]
+}
+
+struct CurrentTimeTool;
+
+#[async_trait]
+impl ToolHandler for CurrentTimeTool {
+    async fn call(&self, _arguments: serde_json::Value) -> Result<String, String> {
+        Ok(chrono::Local::now().to_rfc3339())
+    }
+}
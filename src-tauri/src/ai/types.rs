@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 
 const MODEL_PROVIDER_SEPARATOR: &str = "::";
+/// Default total timeout for non-streaming provider requests
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 120;
+/// Default time a provider stream may go without sending data
+const DEFAULT_STREAM_IDLE_TIMEOUT_SECS: u64 = 60;
 
 /// Extract the actual model ID from a composite ID (e.g., "openai::gpt-4" -> "gpt-4")
 pub fn extract_model_id(composite_or_simple_id: &str) -> &str {
@@ -29,6 +33,23 @@ pub enum MessageContent {
     Parts(Vec<ContentPart>),
 }
 
+impl MessageContent {
+    /// Plain text of the content; image parts are dropped
+    pub fn to_text(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Parts(parts) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    ContentPart::Text { text } => Some(text.as_str()),
+                    ContentPart::ImageUrl { .. } => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
 /// Content part for multimodal messages
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -48,6 +69,8 @@ pub struct ImageUrl {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub role: Role,
+    /// Audio-only responses return `null` content, read as empty text
+    #[serde(deserialize_with = "content_or_empty")]
     pub content: MessageContent,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -55,6 +78,40 @@ pub struct ChatMessage {
     pub tool_call_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<ToolCall>>,
+    /// Spoken response from audio-output models (`modalities: ["text", "audio"]`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio: Option<MessageAudio>,
+}
+
+fn content_or_empty<'de, D>(deserializer: D) -> Result<MessageContent, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let content = Option::<MessageContent>::deserialize(deserializer)?;
+    Ok(content.unwrap_or_else(|| MessageContent::Text(String::new())))
+}
+
+/// Audio returned in an assistant message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageAudio {
+    /// Referenced when the message is sent back as conversation history
+    pub id: String,
+    /// Base64-encoded audio in the requested format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+}
+
+/// Audio output settings for `modalities: ["text", "audio"]` requests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioOutputConfig {
+    /// e.g. "alloy", "verse"
+    pub voice: String,
+    /// e.g. "wav", "mp3", "opus", "flac", "pcm16"
+    pub format: String,
 }
 
 /// Tool call from model
@@ -88,9 +145,10 @@ pub struct ToolFunction {
 }
 
 /// Chat completion request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ChatCompletionRequest {
     pub model: String,
+    /// Conversation history. A single trailing `assistant` message acts as an output prefill.
     pub messages: Vec<ChatMessage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
@@ -106,15 +164,102 @@ pub struct ChatCompletionRequest {
     pub response_format: Option<ResponseFormat>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning_effort: Option<String>,
+    /// Override the reasoning model table: true always sends `reasoning_effort`, false never does
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force_reasoning: Option<bool>,
+    /// Allow the model to request several tool calls at once. Only sent when tools are present;
+    /// providers that don't support it ignore the field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+    /// Output modalities, e.g. `["text", "audio"]` for audio-output models.
+    /// Only forwarded for non-streaming requests.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modalities: Option<Vec<String>>,
+    /// Voice and format used when `modalities` includes "audio"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio: Option<AudioOutputConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    /// Best-effort deterministic sampling, where the provider supports it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<StopSequences>,
+    /// Number of candidate completions to generate (OpenAI-compatible providers only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<u32>,
     #[serde(flatten)]
     pub extra_params: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
+/// Stop sequences, accepted as a single string or an array like the OpenAI API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StopSequences {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl StopSequences {
+    pub fn to_vec(&self) -> Vec<String> {
+        match self {
+            Self::Single(stop) => vec![stop.clone()],
+            Self::Multiple(stops) => stops.clone(),
+        }
+    }
+}
+
+/// Backend-side defaults merged into chat requests where the caller left a field unset
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequestDefaults {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+}
+
+impl RequestDefaults {
+    /// Fill unset request fields from these defaults - explicit per-request values always win
+    pub fn apply_to(&self, request: &mut ChatCompletionRequest) {
+        if request.temperature.is_none() {
+            request.temperature = self.temperature;
+        }
+        if request.reasoning_effort.is_none() {
+            request.reasoning_effort = self.reasoning_effort.clone();
+        }
+        if request.max_tokens.is_none() {
+            request.max_tokens = self.max_tokens;
+        }
+    }
+}
+
 /// Response format for structured outputs
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResponseFormat {
     #[serde(rename = "type")]
-    pub format_type: String, // "json_object" or "text"
+    pub format_type: String, // "json_object", "json_schema" or "text"
+    /// Schema the output must follow; required with "json_schema"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_schema: Option<JsonSchemaFormat>,
+}
+
+/// Named JSON schema for `json_schema` structured outputs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonSchemaFormat {
+    /// 1-64 letters, digits, underscores or dashes
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub schema: serde_json::Value,
+    /// Guarantee schema-conformant output; the provider then only accepts a subset of JSON Schema
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
 }
 
 /// Chat completion response
@@ -131,6 +276,13 @@ pub struct ChatCompletionResponse {
     pub citations: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub search_results: Option<serde_json::Value>,
+    /// Served from the local response cache instead of the provider
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
+    /// Position in the provider chain that served the request (0 = primary);
+    /// only set when fallbacks were given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_index: Option<usize>,
     // Catch-all for other unknown fields
     #[serde(flatten)]
     pub extra: Option<serde_json::Map<String, serde_json::Value>>,
@@ -158,12 +310,100 @@ pub struct Usage {
     pub extra: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
+/// Total timeout for a request given an optional override; None or 0 means the default
+pub fn request_timeout_secs(timeout_secs: Option<u64>) -> u64 {
+    timeout_secs.filter(|secs| *secs > 0).unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECS)
+}
+
 /// Provider credentials passed per-request
 /// Frontend sends these with each AI request - no server-side storage
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderCredentials {
     pub api_key: String,
     pub base_url: String,
+    /// API flavour behind `base_url`: "anthropic" for the Messages API, "azure" for Azure OpenAI
+    /// (`base_url` is the resource endpoint, model ids are deployment names), anything else
+    /// (including empty) for OpenAI-compatible endpoints
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub provider_kind: String,
+    /// Azure OpenAI `api-version` query parameter (default: 2024-10-21); ignored by other providers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_version: Option<String>,
+    /// Largest audio upload the endpoint accepts for transcription, in MB (default: 25, OpenAI's limit).
+    /// Compatible gateways differ, so this can be raised or lowered per provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_audio_upload_mb: Option<u64>,
+    /// Total timeout for non-streaming requests in seconds (default: 120). Commands use the
+    /// same value for their own timeout, so long agentic calls only need this raised.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Streams have no total limit; they fail after this many seconds without data (default: 60)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_idle_timeout_secs: Option<u64>,
+    /// Headers added to every request to OpenAI-compatible endpoints, e.g. OpenRouter's
+    /// `HTTP-Referer`/`X-Title`. Authorization and Content-Type are only replaced if listed here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra_headers: Option<std::collections::HashMap<String, String>>,
+    /// Ask OpenAI-compatible endpoints to end streams with a usage chunk
+    /// (`stream_options.include_usage`, default: on). Turn off for gateways that reject the field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_usage: Option<bool>,
+}
+
+impl ProviderCredentials {
+    pub fn request_timeout_secs(&self) -> u64 {
+        request_timeout_secs(self.timeout_secs)
+    }
+
+    pub fn stream_idle_timeout_secs(&self) -> u64 {
+        self.stream_idle_timeout_secs
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_STREAM_IDLE_TIMEOUT_SECS)
+    }
+
+    pub fn stream_usage(&self) -> bool {
+        self.stream_usage.unwrap_or(true)
+    }
+
+    /// `extra_headers` as a header map
+    pub fn extra_header_map(&self) -> Result<reqwest::header::HeaderMap, String> {
+        match &self.extra_headers {
+            Some(headers) => parse_headers(headers),
+            None => Ok(reqwest::header::HeaderMap::new()),
+        }
+    }
+
+    /// HTTP client for a provider: no total timeout (set per request for non-streaming calls)
+    /// and a read timeout so a stalled stream fails instead of hanging
+    pub fn http_client(&self) -> Result<reqwest::Client, String> {
+        crate::http_client::client_builder()
+            .read_timeout(std::time::Duration::from_secs(self.stream_idle_timeout_secs()))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))
+    }
+}
+
+/// Build a header map from user-supplied headers, rejecting names or values HTTP doesn't allow
+pub fn parse_headers(headers: &std::collections::HashMap<String, String>) -> Result<reqwest::header::HeaderMap, String> {
+    let mut map = reqwest::header::HeaderMap::new();
+    for (name, value) in headers {
+        let header_name = reqwest::header::HeaderName::from_bytes(name.trim().as_bytes())
+            .map_err(|_| format!("Invalid header name '{}'", name))?;
+        let header_value = reqwest::header::HeaderValue::from_str(value)
+            .map_err(|_| format!("Invalid value for header '{}'", name))?;
+        map.insert(header_name, header_value);
+    }
+    Ok(map)
+}
+
+/// Provider tried when the ones before it in a chain fail with a connection, rate-limit or
+/// server error
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderFallback {
+    pub credentials: ProviderCredentials,
+    /// Model to request from this provider; the request's model is kept when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
 }
 
 /// Model info from provider API
@@ -193,6 +433,8 @@ pub struct ChatCompletionChunk {
     pub object: String,
     pub created: u64,
     pub model: String,
+    /// Empty on the usage-only chunk that ends a stream requested with `include_usage`
+    #[serde(default)]
     pub choices: Vec<ChunkChoice>,
     // Perplexity and similar providers include these in final chunk
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -220,14 +462,87 @@ pub struct ChunkDelta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub tool_calls: Option<Vec<ToolCall>>,
+    pub tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+/// Streamed fragment of a tool call. Only the first fragment for an `index` carries the
+/// id and function name; `arguments` arrive as string pieces to be concatenated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallDelta {
+    pub index: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub tool_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<FunctionCallDelta>,
 }
 
-/// Data emitted during streaming - includes both content and metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCallDelta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<String>,
+}
+
+/// Assembles streamed tool-call fragments, keyed by index so parallel calls don't mix
+#[derive(Debug, Default)]
+pub struct ToolCallAccumulator {
+    calls: std::collections::BTreeMap<u32, ToolCall>,
+}
+
+impl ToolCallAccumulator {
+    pub fn push(&mut self, delta: &ToolCallDelta) {
+        let call = self.calls.entry(delta.index).or_insert_with(|| ToolCall {
+            id: String::new(),
+            tool_type: "function".to_string(),
+            function: FunctionCall {
+                name: String::new(),
+                arguments: String::new(),
+            },
+        });
+        if let Some(id) = &delta.id {
+            call.id = id.clone();
+        }
+        if let Some(tool_type) = &delta.tool_type {
+            call.tool_type = tool_type.clone();
+        }
+        if let Some(function) = &delta.function {
+            if let Some(name) = &function.name {
+                call.function.name.push_str(name);
+            }
+            if let Some(arguments) = &function.arguments {
+                call.function.arguments.push_str(arguments);
+            }
+        }
+    }
+
+    /// Calls assembled so far, in index order (arguments may still be incomplete)
+    pub fn snapshot(&self) -> Option<Vec<ToolCall>> {
+        if self.calls.is_empty() {
+            None
+        } else {
+            Some(self.calls.values().cloned().collect())
+        }
+    }
+
+    /// Take the assembled calls, leaving the accumulator empty
+    pub fn take(&mut self) -> Option<Vec<ToolCall>> {
+        let calls = self.snapshot();
+        self.calls.clear();
+        calls
+    }
+}
+
+/// Data emitted during streaming - includes both content and metadata
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StreamChunk {
     /// Text content for this chunk
     pub content: String,
+    /// Choice the chunk belongs to when the request asked for `n` > 1 (otherwise 0)
+    #[serde(default)]
+    pub choice_index: u32,
     /// Citations (only present in final chunk from providers like Perplexity)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub citations: Option<Vec<String>>,
@@ -237,12 +552,74 @@ pub struct StreamChunk {
     /// Token usage (only present in final chunk)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage: Option<Usage>,
+    /// Tool-call fragments in this chunk, for showing calls live as they stream
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call_deltas: Option<Vec<ToolCallDelta>>,
+    /// Fully assembled tool calls, sent once on the chunk that finishes them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+}
+
+/// Partial result emitted when a stream is aborted ("stream-partial-{session_id}")
+/// Carries everything assembled so far so the frontend doesn't depend on every chunk event arriving
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StreamPartialResult {
+    /// Text content accumulated before the abort
+    pub content: String,
+    /// Tool calls captured before the abort (may have incomplete arguments)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+}
+
+/// Final assembled result for JSON-mode streams ("stream-result-{session_id}")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamResult {
+    /// Assembled content, repaired into valid JSON when possible
+    pub content: String,
+    /// True if the assembled content was truncated/invalid and had to be repaired
+    pub json_repaired: bool,
+    /// False if the content could not be turned into valid JSON
+    pub json_valid: bool,
 }
 
 // Image generation, audio transcription, and text-to-speech types
 // These operations are now handled through Rust backend for security
 // (no API keys exposed in browser)
 
+/// Image generation request (OpenAI /images/generations format)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageGenerationRequest {
+    pub model: String,
+    pub prompt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<String>, // e.g. "1024x1024", "1792x1024"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<String>, // "standard", "hd" (dall-e-3); "low", "medium", "high" (gpt-image-1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<String>, // "url" or "b64_json"; gpt-image-1 always returns b64_json
+}
+
+/// Image generation response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageGenerationResponse {
+    pub created: u64,
+    pub data: Vec<GeneratedImage>,
+}
+
+/// One generated image: a (temporary) URL or base64-encoded image bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedImage {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub b64_json: Option<String>,
+    /// Prompt as rewritten by the provider (dall-e-3)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revised_prompt: Option<String>,
+}
+
 /// Audio transcription request (OpenAI Whisper format)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioTranscriptionRequest {
@@ -255,6 +632,35 @@ pub struct AudioTranscriptionRequest {
     pub response_format: Option<String>, // "json", "text", "srt", "verbose_json", "vtt"
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>, // Sampling temperature (0-1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp_granularities: Option<Vec<String>>, // "segment" and/or "word" (verbose_json only)
+}
+
+/// Event from a realtime (websocket) transcription session. With server-side voice activity
+/// detection each pause in speech closes an utterance, identified by `item_id`.
+#[derive(Debug, Clone)]
+pub enum RealtimeTranscriptionEvent {
+    /// More text for an utterance still being transcribed
+    Delta { item_id: String, delta: String },
+    /// Final text of an utterance
+    Completed { item_id: String, transcript: String },
+}
+
+/// Transcription models that reject `verbose_json` and only return plain json/text
+const NON_VERBOSE_TRANSCRIPTION_MODELS: &[&str] = &["gpt-4o-transcribe", "gpt-4o-mini-transcribe"];
+
+/// Check whether a transcription model can stream text deltas over SSE
+/// (the same newer models that lack `verbose_json`)
+pub fn supports_streaming_transcription(model: &str) -> bool {
+    !supports_verbose_json(model)
+}
+
+/// Check whether a transcription model can return `verbose_json` (segments, words, duration)
+pub fn supports_verbose_json(model: &str) -> bool {
+    let model_id = extract_model_id(model);
+    !NON_VERBOSE_TRANSCRIPTION_MODELS
+        .iter()
+        .any(|prefix| model_id.starts_with(prefix))
 }
 
 /// Audio transcription response
@@ -271,6 +677,38 @@ pub struct AudioTranscriptionResponse {
     pub segments: Option<Vec<serde_json::Value>>, // Detailed segment-level timestamps
 }
 
+/// Transcript with the spoken language, returned by the local and cloud transcription commands
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionResult {
+    pub text: String,
+    /// ISO 639-1 code ("pl"); None when the provider doesn't report it
+    pub language: Option<String>,
+    pub duration_ms: Option<u64>,
+    /// Local inference backend ("cpu", "metal", "cuda"); not set for cloud transcriptions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+}
+
+/// Where a transcription pipeline gets its raw transcript from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranscriptionSource {
+    /// A downloaded local whisper model
+    Local { model_id: String },
+    /// A cloud transcription model
+    Cloud { model: String, credentials: ProviderCredentials },
+}
+
+/// Raw transcript and its cleaned-up version from a chat model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefinedTranscription {
+    pub raw_text: String,
+    /// Empty when nothing was transcribed
+    pub refined_text: String,
+    /// ISO 639-1 code, when requested or detected
+    pub language: Option<String>,
+}
+
 /// Text-to-speech request (OpenAI TTS format)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TextToSpeechRequest {
@@ -0,0 +1,89 @@
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use crate::audio::format::{detect_container, AudioContainer};
+use crate::audio::types::AudioRecordingError;
+use crate::audio::wav::DecodedAudio;
+
+/// Decode audio in any supported container (WAV, MP3, M4A/AAC, FLAC, Ogg Vorbis, WebM/MKV)
+/// into f32 samples. WAV goes through hound; everything else through symphonia.
+pub fn decode_audio(data: &[u8]) -> Result<DecodedAudio, AudioRecordingError> {
+    let container = detect_container(data);
+    if container == Some(AudioContainer::Wav) {
+        return crate::audio::wav::decode_wav(data);
+    }
+
+    let err = |e: SymphoniaError| AudioRecordingError::ProcessingError(format!("Failed to decode audio: {}", e));
+
+    let mut hint = Hint::new();
+    if let Some(container) = container {
+        hint.with_extension(container.extension());
+    }
+    let source = MediaSourceStream::new(Box::new(std::io::Cursor::new(data.to_vec())), Default::default());
+    let probed = symphonia::default::get_probe()
+        .format(&hint, source, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(err)?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| AudioRecordingError::ProcessingError("No audio track found".to_string()))?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(0);
+    let mut channels = track.codec_params.channels.map(|c| c.count() as u16).unwrap_or(0);
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(err)?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(err(e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                let spec = *decoded.spec();
+                let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+                buffer.copy_interleaved_ref(decoded);
+                samples.extend_from_slice(buffer.samples());
+                sample_rate = spec.rate;
+                channels = spec.channels.count() as u16;
+            }
+            // A corrupt frame only loses that frame
+            Err(SymphoniaError::DecodeError(e)) => {
+                eprintln!("[Audio] Skipping undecodable frame: {}", e);
+            }
+            Err(e) => return Err(err(e)),
+        }
+    }
+
+    if sample_rate == 0 || channels == 0 {
+        return Err(AudioRecordingError::ProcessingError(
+            "Audio has no decodable samples".to_string(),
+        ));
+    }
+
+    Ok(DecodedAudio {
+        samples,
+        sample_rate,
+        channels,
+    })
+}
+
+/// Decode audio in any supported container and re-encode it as 16-bit PCM WAV
+pub fn transcode_to_wav(data: &[u8]) -> Result<Vec<u8>, AudioRecordingError> {
+    let decoded = decode_audio(data)?;
+    crate::audio::wav::encode_wav(&decoded.samples, decoded.sample_rate, decoded.channels)
+}
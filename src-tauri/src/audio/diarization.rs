@@ -0,0 +1,113 @@
+//! Approximate, local-only speaker labelling for transcription segments.
+//!
+//! This is NOT real diarization: it compares simple acoustic features (loudness, zero-crossing
+//! rate, high-frequency energy) per segment and groups segments whose features are close.
+//! It works reasonably for two or three clearly different voices recorded on one microphone,
+//! but it will merge similar-sounding speakers, split one speaker whose loudness changes a lot,
+//! and cannot separate overlapping speech. Treat labels as hints, not ground truth.
+
+/// Maximum number of distinct speakers the detector will create
+const MAX_SPEAKERS: usize = 4;
+
+/// Feature distance above which a segment is considered a different speaker
+const SPEAKER_CHANGE_THRESHOLD: f32 = 0.35;
+
+/// Segments shorter than this carry too little signal and inherit the previous speaker
+const MIN_SEGMENT_MS: f32 = 300.0;
+
+#[derive(Clone, Copy)]
+struct Features {
+    loudness: f32,
+    zcr: f32,
+    hf_ratio: f32,
+}
+
+impl Features {
+    fn distance(&self, other: &Features) -> f32 {
+        let dl = self.loudness - other.loudness;
+        let dz = self.zcr - other.zcr;
+        let dh = self.hf_ratio - other.hf_ratio;
+        (dl * dl + dz * dz + dh * dh).sqrt()
+    }
+}
+
+struct Speaker {
+    centroid: Features,
+    count: f32,
+}
+
+/// Compute normalized features for a mono slice; None if the slice is silent or too short
+fn segment_features(samples: &[f32], sample_rate: u32) -> Option<Features> {
+    let min_len = (sample_rate as f32 * MIN_SEGMENT_MS / 1000.0) as usize;
+    if samples.len() < min_len.max(2) {
+        return None;
+    }
+
+    let energy: f32 = samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32;
+    if energy <= 1e-8 {
+        return None;
+    }
+
+    let crossings = samples
+        .windows(2)
+        .filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0))
+        .count();
+    let diff_energy: f32 = samples
+        .windows(2)
+        .map(|w| (w[1] - w[0]) * (w[1] - w[0]))
+        .sum::<f32>()
+        / (samples.len() - 1) as f32;
+
+    // Map loudness from roughly [-60, 0] dBFS to [0, 1]
+    let db = 10.0 * energy.log10();
+    Some(Features {
+        loudness: ((db + 60.0) / 60.0).clamp(0.0, 1.0),
+        zcr: crossings as f32 / samples.len() as f32,
+        hf_ratio: (diff_energy / (4.0 * energy)).clamp(0.0, 1.0),
+    })
+}
+
+/// Tag each segment (OpenAI verbose_json shape: `start`/`end` in seconds) with a `speaker`
+/// label such as "Speaker 1", based on the mono samples of the same clip.
+pub fn label_speakers(samples: &[f32], sample_rate: u32, segments: &mut [serde_json::Value]) {
+    let mut speakers: Vec<Speaker> = Vec::new();
+    let mut current: usize = 0;
+
+    for segment in segments.iter_mut() {
+        let start = segment.get("start").and_then(|v| v.as_f64()).unwrap_or(0.0);
+        let end = segment.get("end").and_then(|v| v.as_f64()).unwrap_or(start);
+        let from = ((start * sample_rate as f64) as usize).min(samples.len());
+        let to = ((end * sample_rate as f64) as usize).clamp(from, samples.len());
+
+        if let Some(features) = segment_features(&samples[from..to], sample_rate) {
+            let nearest = speakers
+                .iter()
+                .enumerate()
+                .map(|(i, s)| (i, s.centroid.distance(&features)))
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+
+            current = match nearest {
+                Some((i, dist)) if dist <= SPEAKER_CHANGE_THRESHOLD || speakers.len() >= MAX_SPEAKERS => i,
+                _ => {
+                    speakers.push(Speaker { centroid: features, count: 0.0 });
+                    speakers.len() - 1
+                }
+            };
+
+            // Running mean of the assigned speaker's features
+            let speaker = &mut speakers[current];
+            speaker.count += 1.0;
+            let w = 1.0 / speaker.count;
+            speaker.centroid.loudness += (features.loudness - speaker.centroid.loudness) * w;
+            speaker.centroid.zcr += (features.zcr - speaker.centroid.zcr) * w;
+            speaker.centroid.hf_ratio += (features.hf_ratio - speaker.centroid.hf_ratio) * w;
+        }
+
+        if let Some(obj) = segment.as_object_mut() {
+            obj.insert(
+                "speaker".to_string(),
+                serde_json::json!(format!("Speaker {}", current + 1)),
+            );
+        }
+    }
+}
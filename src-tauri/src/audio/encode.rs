@@ -0,0 +1,109 @@
+use flacenc::component::BitRepr;
+use flacenc::error::Verify;
+use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+use crate::audio::types::{AudioOutputFormat, AudioRecordingError};
+
+/// Sample rates libopus encodes natively; anything else is resampled to 48 kHz
+const OPUS_SAMPLE_RATES: [u32; 5] = [8000, 12000, 16000, 24000, 48000];
+/// Ogg Opus granule positions are always counted at 48 kHz
+const OPUS_GRANULE_RATE: u64 = 48000;
+const OPUS_FRAME_MS: u32 = 20;
+/// Plenty for speech; roughly a tenth of the size of 16-bit PCM at 16 kHz
+const OPUS_BITRATE: i32 = 24_000;
+/// Largest Opus packet we accept from the encoder
+const OPUS_MAX_PACKET: usize = 4000;
+const OGG_STREAM_SERIAL: u32 = 1;
+
+/// Encode mono f32 samples in the requested container
+pub fn encode(samples: &[f32], sample_rate: u32, format: AudioOutputFormat) -> Result<Vec<u8>, AudioRecordingError> {
+    match format {
+        AudioOutputFormat::Wav => crate::audio::wav::encode_wav(samples, sample_rate, 1),
+        AudioOutputFormat::Flac => encode_flac(samples, sample_rate),
+        AudioOutputFormat::Opus => encode_opus(samples, sample_rate),
+    }
+}
+
+/// Lossless 16-bit mono FLAC
+fn encode_flac(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>, AudioRecordingError> {
+    let pcm: Vec<i32> = samples
+        .iter()
+        .map(|&s| (s * 32767.0).clamp(-32768.0, 32767.0) as i32)
+        .collect();
+
+    let config = flacenc::config::Encoder::default()
+        .into_verified()
+        .map_err(|(_, e)| AudioRecordingError::EncodingError(format!("Invalid FLAC config: {:?}", e)))?;
+    let source = flacenc::source::MemSource::from_samples(&pcm, 1, 16, sample_rate as usize);
+    let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+        .map_err(|e| AudioRecordingError::EncodingError(format!("FLAC encoding failed: {:?}", e)))?;
+
+    let mut sink = flacenc::bitsink::ByteSink::new();
+    stream
+        .write(&mut sink)
+        .map_err(|e| AudioRecordingError::EncodingError(format!("FLAC encoding failed: {:?}", e)))?;
+    Ok(sink.as_slice().to_vec())
+}
+
+/// Mono Opus (VoIP tuning) in an Ogg container, per RFC 7845
+fn encode_opus(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>, AudioRecordingError> {
+    let opus_err = |e: opus::Error| AudioRecordingError::EncodingError(format!("Opus encoding failed: {}", e));
+
+    let (samples, rate) = if OPUS_SAMPLE_RATES.contains(&sample_rate) {
+        (samples.to_vec(), sample_rate)
+    } else {
+        (crate::audio::resample::resample(samples, sample_rate, 48000), 48000)
+    };
+    let to_granule = |frames: u64| frames * OPUS_GRANULE_RATE / rate as u64;
+
+    let mut encoder = opus::Encoder::new(rate, opus::Channels::Mono, opus::Application::Voip).map_err(opus_err)?;
+    encoder.set_bitrate(opus::Bitrate::Bits(OPUS_BITRATE)).map_err(opus_err)?;
+    let pre_skip = to_granule(encoder.get_lookahead().map_err(opus_err)?.max(0) as u64);
+
+    let mut writer = PacketWriter::new(Vec::new());
+    let write_err = |e: std::io::Error| AudioRecordingError::EncodingError(format!("Ogg write failed: {}", e));
+
+    let mut head = Vec::with_capacity(19);
+    head.extend_from_slice(b"OpusHead");
+    head.push(1); // version
+    head.push(1); // channel count
+    head.extend_from_slice(&(pre_skip as u16).to_le_bytes());
+    head.extend_from_slice(&sample_rate.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    head.push(0); // mapping family: mono/stereo
+    writer
+        .write_packet(head, OGG_STREAM_SERIAL, PacketWriteEndInfo::EndPage, 0)
+        .map_err(write_err)?;
+
+    let vendor = b"zakip-voice";
+    let mut tags = Vec::new();
+    tags.extend_from_slice(b"OpusTags");
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor);
+    tags.extend_from_slice(&0u32.to_le_bytes()); // no user comments
+    writer
+        .write_packet(tags, OGG_STREAM_SERIAL, PacketWriteEndInfo::EndPage, 0)
+        .map_err(write_err)?;
+
+    let frame_len = (rate * OPUS_FRAME_MS / 1000) as usize;
+    let frame_count = samples.len().div_ceil(frame_len).max(1);
+    let mut frame = vec![0.0f32; frame_len];
+    for index in 0..frame_count {
+        // The last frame is zero-padded; its granule position marks where real audio ends
+        let start = index * frame_len;
+        let end = (start + frame_len).min(samples.len());
+        frame.fill(0.0);
+        if start < end {
+            frame[..end - start].copy_from_slice(&samples[start..end]);
+        }
+
+        let packet = encoder.encode_vec_float(&frame, OPUS_MAX_PACKET).map_err(opus_err)?;
+        let last = index + 1 == frame_count;
+        let granule = pre_skip + to_granule(if last { samples.len() } else { end } as u64);
+        let end_info = if last { PacketWriteEndInfo::EndStream } else { PacketWriteEndInfo::NormalPacket };
+        writer
+            .write_packet(packet, OGG_STREAM_SERIAL, end_info, granule)
+            .map_err(write_err)?;
+    }
+
+    Ok(writer.into_inner())
+}
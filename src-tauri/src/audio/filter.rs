@@ -0,0 +1,94 @@
+/// Apply a 2nd-order Butterworth high-pass (RBJ biquad) to mono samples in place,
+/// removing rumble (HVAC, handling noise) below `cutoff_hz`
+pub fn high_pass(samples: &mut [f32], sample_rate: u32, cutoff_hz: f32) {
+    let nyquist = sample_rate as f32 / 2.0;
+    if samples.is_empty() || cutoff_hz <= 0.0 || cutoff_hz >= nyquist {
+        return;
+    }
+
+    let omega = 2.0 * std::f32::consts::PI * cutoff_hz / sample_rate as f32;
+    let (sin, cos) = omega.sin_cos();
+    // Q = 1/sqrt(2) for a maximally flat (Butterworth) response: alpha = sin / (2Q)
+    let alpha = sin * std::f32::consts::FRAC_1_SQRT_2;
+
+    let a0 = 1.0 + alpha;
+    let b0 = (1.0 + cos) / 2.0 / a0;
+    let b1 = -(1.0 + cos) / a0;
+    let b2 = b0;
+    let a1 = -2.0 * cos / a0;
+    let a2 = (1.0 - alpha) / a0;
+
+    let (mut x1, mut x2, mut y1, mut y2) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+    for sample in samples.iter_mut() {
+        let x0 = *sample;
+        let y0 = b0 * x0 + b1 * x1 + b2 * x2 - a1 * y1 - a2 * y2;
+        x2 = x1;
+        x1 = x0;
+        y2 = y1;
+        y1 = y0;
+        *sample = y0;
+    }
+}
+
+/// Largest boost `normalize_peak` applies, so near-silent recordings don't turn into loud noise
+const MAX_NORMALIZE_GAIN_DB: f32 = 30.0;
+
+/// Scale samples so their peak reaches `target_peak_dbfs`, only ever boosting (never attenuating).
+/// Returns the applied gain in dB (0 when the peak is already at or above target).
+pub fn normalize_peak(samples: &mut [f32], target_peak_dbfs: f32) -> f32 {
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    if peak <= f32::EPSILON {
+        return 0.0;
+    }
+
+    // Targets above 0 dBFS would clip
+    let target = 10f32.powf(target_peak_dbfs.min(0.0) / 20.0);
+    if peak >= target {
+        return 0.0;
+    }
+
+    let gain_db = (20.0 * (target / peak).log10()).min(MAX_NORMALIZE_GAIN_DB);
+    let gain = 10f32.powf(gain_db / 20.0);
+    for sample in samples.iter_mut() {
+        *sample *= gain;
+    }
+    gain_db
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 16_000;
+
+    fn sine(freq_hz: f32, seconds: f32) -> Vec<f32> {
+        let len = (SAMPLE_RATE as f32 * seconds) as usize;
+        (0..len)
+            .map(|i| (2.0 * std::f32::consts::PI * freq_hz * i as f32 / SAMPLE_RATE as f32).sin() * 0.5)
+            .collect()
+    }
+
+    /// RMS after skipping the first 100ms, so the filter's start-up transient doesn't count
+    fn settled_rms(samples: &[f32]) -> f32 {
+        let settled = &samples[SAMPLE_RATE as usize / 10..];
+        (settled.iter().map(|s| s * s).sum::<f32>() / settled.len() as f32).sqrt()
+    }
+
+    fn gain_db(freq_hz: f32) -> f32 {
+        let input = sine(freq_hz, 1.0);
+        let mut output = input.clone();
+        high_pass(&mut output, SAMPLE_RATE, 80.0);
+        20.0 * (settled_rms(&output) / settled_rms(&input)).log10()
+    }
+
+    #[test]
+    fn high_pass_removes_rumble() {
+        // A 2nd-order filter falls off 12 dB/octave: ~-15 dB at 30 Hz for an 80 Hz cutoff
+        assert!(gain_db(30.0) < -12.0, "30 Hz gain was {} dB", gain_db(30.0));
+    }
+
+    #[test]
+    fn high_pass_keeps_speech_band() {
+        assert!(gain_db(1_000.0).abs() < 1.0, "1 kHz gain was {} dB", gain_db(1_000.0));
+    }
+}
@@ -0,0 +1,77 @@
+/// Audio container formats recognized by sniffing leading bytes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AudioContainer {
+    Wav,
+    Mp3,
+    Ogg,
+    Flac,
+    Mp4,
+    Webm,
+}
+
+impl AudioContainer {
+    /// Canonical MIME type
+    pub fn mime(&self) -> &'static str {
+        match self {
+            Self::Wav => "audio/wav",
+            Self::Mp3 => "audio/mpeg",
+            Self::Ogg => "audio/ogg",
+            Self::Flac => "audio/flac",
+            Self::Mp4 => "audio/mp4",
+            Self::Webm => "audio/webm",
+        }
+    }
+
+    /// File extension providers use to recognize the upload
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Wav => "wav",
+            Self::Mp3 => "mp3",
+            Self::Ogg => "ogg",
+            Self::Flac => "flac",
+            Self::Mp4 => "m4a",
+            Self::Webm => "webm",
+        }
+    }
+
+    /// Whether `mime` is an accepted alias for this container
+    pub fn matches_mime(&self, mime: &str) -> bool {
+        let mime = mime.split(';').next().unwrap_or(mime).trim().to_ascii_lowercase();
+        let aliases: &[&str] = match self {
+            Self::Wav => &["audio/wav", "audio/x-wav", "audio/wave", "audio/vnd.wave"],
+            Self::Mp3 => &["audio/mpeg", "audio/mp3"],
+            Self::Ogg => &["audio/ogg", "audio/opus"],
+            Self::Flac => &["audio/flac", "audio/x-flac"],
+            Self::Mp4 => &["audio/mp4", "audio/m4a", "audio/x-m4a", "audio/aac"],
+            Self::Webm => &["audio/webm", "video/webm"],
+        };
+        aliases.contains(&mime.as_str())
+    }
+}
+
+/// Detect the container from the first bytes of the file
+pub fn detect_container(data: &[u8]) -> Option<AudioContainer> {
+    if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WAVE" {
+        return Some(AudioContainer::Wav);
+    }
+    if data.len() >= 4 && &data[0..4] == b"OggS" {
+        return Some(AudioContainer::Ogg);
+    }
+    if data.len() >= 4 && &data[0..4] == b"fLaC" {
+        return Some(AudioContainer::Flac);
+    }
+    if data.len() >= 12 && &data[4..8] == b"ftyp" {
+        return Some(AudioContainer::Mp4);
+    }
+    if data.len() >= 4 && data[0..4] == [0x1A, 0x45, 0xDF, 0xA3] {
+        return Some(AudioContainer::Webm);
+    }
+    if data.len() >= 3 && &data[0..3] == b"ID3" {
+        return Some(AudioContainer::Mp3);
+    }
+    // Bare MPEG audio frame sync
+    if data.len() >= 2 && data[0] == 0xFF && (data[1] & 0xE0) == 0xE0 {
+        return Some(AudioContainer::Mp3);
+    }
+    None
+}
@@ -1,5 +1,14 @@
+pub mod decode;
+pub mod diarization;
+pub mod encode;
+pub mod filter;
+pub mod format;
 pub mod recorder;
+pub mod resample;
+pub mod silence;
 pub mod types;
+pub mod wav;
+pub mod waveform;
 
 pub use recorder::AudioRecordingManager;
 pub use types::*;
@@ -1,11 +1,17 @@
 use crate::audio::types::*;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::thread::{self, JoinHandle};
 use std::sync::mpsc::{self, Sender, Receiver};
 use tauri::Emitter;
 
+/// Sample magnitude reported as clipping; integer devices converted to f32 top out
+/// just below 1.0, so full scale is matched with a little headroom
+const CLIPPING_LEVEL: f32 = 0.999;
+
 /// Commands sent to the audio thread
 enum AudioCommand {
     StartRecording {
@@ -21,12 +27,58 @@ enum AudioCommand {
         session_id: String,
         response: Sender<Result<(), AudioRecordingError>>,
     },
+    PauseRecording {
+        session_id: String,
+        response: Sender<Result<(), AudioRecordingError>>,
+    },
+    ResumeRecording {
+        session_id: String,
+        response: Sender<Result<(), AudioRecordingError>>,
+    },
     ForceReset {
         response: Sender<bool>,
     },
+    LiveTap {
+        session_id: String,
+        response: Sender<Result<LiveAudioTap, AudioRecordingError>>,
+    },
+    SetPreRoll {
+        pre_roll_ms: u32,
+        response: Sender<Result<(), AudioRecordingError>>,
+    },
+    /// Sent from the cpal error callback when the input device disappears
+    DeviceLost {
+        session_id: String,
+    },
+    /// Sent from the input callback once `max_duration_ms` of audio has been captured
+    MaxDurationReached {
+        session_id: String,
+    },
     Shutdown,
 }
 
+/// Read-only view of an active session's captured (mono) samples, for live processing
+pub struct LiveAudioTap {
+    samples: Arc<Mutex<Vec<f32>>>,
+    pub sample_rate: u32,
+}
+
+impl LiveAudioTap {
+    /// Copy of the samples captured from `from` onwards
+    pub fn samples_from(&self, from: usize) -> Vec<f32> {
+        let guard = match self.samples.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        guard.get(from..).map(|tail| tail.to_vec()).unwrap_or_default()
+    }
+
+    /// False once the session was stopped or cancelled (the recorder dropped its buffer)
+    pub fn is_recording(&self) -> bool {
+        Arc::strong_count(&self.samples) > 1
+    }
+}
+
 /// Uses a dedicated thread for audio operations since cpal::Stream is not Send
 pub struct AudioRecordingManager {
     command_sender: Sender<AudioCommand>,
@@ -40,9 +92,10 @@ unsafe impl Sync for AudioRecordingManager {}
 impl AudioRecordingManager {
     pub fn new() -> Self {
         let (tx, rx) = mpsc::channel();
+        let self_sender = tx.clone();
 
         let audio_thread = thread::spawn(move || {
-            audio_thread_main(rx);
+            audio_thread_main(rx, self_sender);
         });
 
         Self {
@@ -63,7 +116,7 @@ impl AudioRecordingManager {
         rx.recv().map_err(|_| AudioRecordingError::StreamInitFailed("Audio thread not responding".to_string()))?
     }
 
-    /// Stop the current recording and return WAV data
+    /// Stop a recording session and return WAV data
     pub fn stop_recording(&self, session_id: &str) -> Result<AudioRecordingResult, AudioRecordingError> {
         let (tx, rx) = mpsc::channel();
         self.command_sender.send(AudioCommand::StopRecording {
@@ -74,7 +127,7 @@ impl AudioRecordingManager {
         rx.recv().map_err(|_| AudioRecordingError::StreamInitFailed("Audio thread not responding".to_string()))?
     }
 
-    /// Cancel the current recording without returning data
+    /// Cancel a recording session without returning data
     pub fn cancel_recording(&self, session_id: &str) -> Result<(), AudioRecordingError> {
         let (tx, rx) = mpsc::channel();
         self.command_sender.send(AudioCommand::CancelRecording {
@@ -85,6 +138,52 @@ impl AudioRecordingManager {
         rx.recv().map_err(|_| AudioRecordingError::StreamInitFailed("Audio thread not responding".to_string()))?
     }
 
+    /// Pause a recording session: the stream stays open but captured audio is discarded
+    pub fn pause_recording(&self, session_id: &str) -> Result<(), AudioRecordingError> {
+        let (tx, rx) = mpsc::channel();
+        self.command_sender.send(AudioCommand::PauseRecording {
+            session_id: session_id.to_string(),
+            response: tx,
+        }).map_err(|_| AudioRecordingError::StreamInitFailed("Audio thread not responding".to_string()))?;
+
+        rx.recv().map_err(|_| AudioRecordingError::StreamInitFailed("Audio thread not responding".to_string()))?
+    }
+
+    /// Resume a paused recording session
+    pub fn resume_recording(&self, session_id: &str) -> Result<(), AudioRecordingError> {
+        let (tx, rx) = mpsc::channel();
+        self.command_sender.send(AudioCommand::ResumeRecording {
+            session_id: session_id.to_string(),
+            response: tx,
+        }).map_err(|_| AudioRecordingError::StreamInitFailed("Audio thread not responding".to_string()))?;
+
+        rx.recv().map_err(|_| AudioRecordingError::StreamInitFailed("Audio thread not responding".to_string()))?
+    }
+
+    /// Arm (or disarm with 0) the rolling pre-roll buffer.
+    /// While armed the microphone stays open so the last `pre_roll_ms` of audio can be
+    /// prepended to the next recording.
+    pub fn set_pre_roll(&self, pre_roll_ms: u32) -> Result<(), AudioRecordingError> {
+        let (tx, rx) = mpsc::channel();
+        self.command_sender.send(AudioCommand::SetPreRoll {
+            pre_roll_ms,
+            response: tx,
+        }).map_err(|_| AudioRecordingError::StreamInitFailed("Audio thread not responding".to_string()))?;
+
+        rx.recv().map_err(|_| AudioRecordingError::StreamInitFailed("Audio thread not responding".to_string()))?
+    }
+
+    /// Tap into the samples of an active session while it is still recording
+    pub fn live_tap(&self, session_id: &str) -> Result<LiveAudioTap, AudioRecordingError> {
+        let (tx, rx) = mpsc::channel();
+        self.command_sender.send(AudioCommand::LiveTap {
+            session_id: session_id.to_string(),
+            response: tx,
+        }).map_err(|_| AudioRecordingError::StreamInitFailed("Audio thread not responding".to_string()))?;
+
+        rx.recv().map_err(|_| AudioRecordingError::StreamInitFailed("Audio thread not responding".to_string()))?
+    }
+
     pub fn force_reset(&self) -> bool {
         let (tx, rx) = mpsc::channel();
         if self.command_sender.send(AudioCommand::ForceReset { response: tx }).is_ok() {
@@ -106,37 +205,126 @@ struct RecordingState {
     session: AudioRecordingSession,
     samples: Arc<Mutex<Vec<f32>>>,
     stream: cpal::Stream,
+    app_handle: Option<tauri::AppHandle>,
+    min_duration_ms: u64,
+    high_pass_hz: Option<f32>,
+    target_peak_dbfs: Option<f32>,
+    trim_silence: bool,
+    output_format: AudioOutputFormat,
+    waveform_buckets: usize,
+    /// Checked by the input callback; while set, captured audio is dropped
+    paused: Arc<AtomicBool>,
+    /// Recording time accumulated before the current (or last) pause
+    active_ms: u64,
+    /// When the current active stretch began; None while paused
+    resumed_at: Option<std::time::Instant>,
+}
+
+impl RecordingState {
+    /// Recorded time excluding pauses
+    fn active_duration_ms(&self) -> u64 {
+        self.active_ms + self.resumed_at.map(|at| at.elapsed().as_millis() as u64).unwrap_or(0)
+    }
+}
+
+/// Passive input stream keeping the most recent audio in a ring buffer (lives in audio thread)
+struct PreRollState {
+    buffer: Arc<Mutex<VecDeque<f32>>>,
+    sample_rate: u32,
+    device_name: Option<String>,
+    _stream: cpal::Stream,
 }
 
 /// Main function for the audio thread
-fn audio_thread_main(receiver: Receiver<AudioCommand>) {
-    let mut active_recording: Option<RecordingState> = None;
+fn audio_thread_main(receiver: Receiver<AudioCommand>, self_sender: Sender<AudioCommand>) {
+    // Concurrent sessions (e.g. microphone and loopback), each with its own stream and buffer
+    let mut sessions: HashMap<String, RecordingState> = HashMap::new();
+    let mut pre_roll: Option<PreRollState> = None;
 
     loop {
         match receiver.recv() {
             Ok(command) => match command {
                 AudioCommand::StartRecording { config, app_handle, response } => {
-                    let result = start_recording_internal(&mut active_recording, config, app_handle);
+                    let result = start_recording_internal(&mut sessions, pre_roll.as_ref(), config, app_handle, self_sender.clone());
                     let _ = response.send(result);
                 }
                 AudioCommand::StopRecording { session_id, response } => {
-                    let result = stop_recording_internal(&mut active_recording, &session_id);
+                    let result = stop_recording_internal(&mut sessions, &session_id);
                     let _ = response.send(result);
                 }
                 AudioCommand::CancelRecording { session_id, response } => {
-                    let result = cancel_recording_internal(&mut active_recording, &session_id);
+                    let result = cancel_recording_internal(&mut sessions, &session_id);
+                    let _ = response.send(result);
+                }
+                AudioCommand::PauseRecording { session_id, response } => {
+                    let result = find_session_mut(&mut sessions, &session_id).map(|state| {
+                        if let Some(resumed_at) = state.resumed_at.take() {
+                            state.active_ms += resumed_at.elapsed().as_millis() as u64;
+                            state.paused.store(true, Ordering::Relaxed);
+                        }
+                    });
+                    let _ = response.send(result);
+                }
+                AudioCommand::ResumeRecording { session_id, response } => {
+                    let result = find_session_mut(&mut sessions, &session_id).map(|state| {
+                        if state.resumed_at.is_none() {
+                            state.resumed_at = Some(std::time::Instant::now());
+                            state.paused.store(false, Ordering::Relaxed);
+                        }
+                    });
                     let _ = response.send(result);
                 }
                 AudioCommand::ForceReset { response } => {
-                    let had_recording = active_recording.is_some();
+                    let had_recording = !sessions.is_empty();
                     if had_recording {
-                        if let Some(state) = active_recording.take() {
-                            drop(state.stream);
-                            eprintln!("[AudioRecorder] Force reset: cleared stuck recording session");
-                        }
+                        // Dropping each state drops its stream
+                        eprintln!("[AudioRecorder] Force reset: cleared {} recording session(s)", sessions.len());
+                        sessions.clear();
                     }
                     let _ = response.send(had_recording);
                 }
+                AudioCommand::LiveTap { session_id, response } => {
+                    let result = find_session(&sessions, &session_id).map(|state| LiveAudioTap {
+                        samples: Arc::clone(&state.samples),
+                        sample_rate: state.session.sample_rate,
+                    });
+                    let _ = response.send(result);
+                }
+                AudioCommand::SetPreRoll { pre_roll_ms, response } => {
+                    // Drop any existing passive stream before (re)arming
+                    pre_roll = None;
+                    let result = if pre_roll_ms > 0 {
+                        match start_pre_roll_stream(pre_roll_ms) {
+                            Ok(state) => {
+                                pre_roll = Some(state);
+                                Ok(())
+                            }
+                            Err(e) => Err(e),
+                        }
+                    } else {
+                        Ok(())
+                    };
+                    let _ = response.send(result);
+                }
+                AudioCommand::DeviceLost { session_id } => {
+                    // Finalize with whatever was captured so the user doesn't lose it
+                    let app_handle = sessions.get(&session_id).and_then(|state| state.app_handle.clone());
+                    if let Ok(result) = stop_recording_internal(&mut sessions, &session_id) {
+                        eprintln!("[AudioRecorder] Input device lost, finalized session {}", session_id);
+                        if let Some(app) = app_handle {
+                            let _ = app.emit(&format!("recording-device-lost-{}", session_id), &result);
+                        }
+                    }
+                }
+                AudioCommand::MaxDurationReached { session_id } => {
+                    let app_handle = sessions.get(&session_id).and_then(|state| state.app_handle.clone());
+                    if let Ok(result) = stop_recording_internal(&mut sessions, &session_id) {
+                        eprintln!("[AudioRecorder] Max duration reached, finalized session {}", session_id);
+                        if let Some(app) = app_handle {
+                            let _ = app.emit("recording-max-duration-reached", &result);
+                        }
+                    }
+                }
                 AudioCommand::Shutdown => {
                     break;
                 }
@@ -149,24 +337,153 @@ fn audio_thread_main(receiver: Receiver<AudioCommand>) {
     }
 }
 
-fn start_recording_internal(
-    active_recording: &mut Option<RecordingState>,
-    config: AudioRecordingConfig,
-    app_handle: Option<tauri::AppHandle>,
-) -> Result<AudioRecordingSession, AudioRecordingError> {
-    // Check if already recording
-    if active_recording.is_some() {
-        return Err(AudioRecordingError::StreamInitFailed(
-            "Recording already in progress".to_string(),
-        ));
-    }
+/// Look up a session; an unknown id is a mismatch while other sessions are recording
+fn find_session<'a>(
+    sessions: &'a HashMap<String, RecordingState>,
+    session_id: &str,
+) -> Result<&'a RecordingState, AudioRecordingError> {
+    sessions.get(session_id).ok_or(if sessions.is_empty() {
+        AudioRecordingError::NoActiveSession
+    } else {
+        AudioRecordingError::SessionMismatch
+    })
+}
 
-    // Get default audio input device
+fn find_session_mut<'a>(
+    sessions: &'a mut HashMap<String, RecordingState>,
+    session_id: &str,
+) -> Result<&'a mut RecordingState, AudioRecordingError> {
+    find_session(sessions, session_id)?;
+    sessions.get_mut(session_id).ok_or(AudioRecordingError::NoActiveSession)
+}
+
+/// Remove a session from the map, with the same errors as `find_session`
+fn take_session(
+    sessions: &mut HashMap<String, RecordingState>,
+    session_id: &str,
+) -> Result<RecordingState, AudioRecordingError> {
+    find_session(sessions, session_id)?;
+    sessions.remove(session_id).ok_or(AudioRecordingError::NoActiveSession)
+}
+
+/// Open a passive stream on the default input device that keeps the last `pre_roll_ms` of mono audio
+fn start_pre_roll_stream(pre_roll_ms: u32) -> Result<PreRollState, AudioRecordingError> {
     let host = cpal::default_host();
     let device = host
         .default_input_device()
         .ok_or(AudioRecordingError::NoInputDevice)?;
 
+    let stream_config = device
+        .default_input_config()
+        .map_err(|e| AudioRecordingError::StreamInitFailed(e.to_string()))?
+        .config();
+
+    let sample_rate = stream_config.sample_rate.0;
+    let channels = stream_config.channels as usize;
+    let capacity = (sample_rate as u64 * pre_roll_ms as u64 / 1000) as usize;
+
+    let buffer: Arc<Mutex<VecDeque<f32>>> = Arc::new(Mutex::new(VecDeque::with_capacity(capacity)));
+    let buffer_clone = Arc::clone(&buffer);
+
+    let err_fn = |err| eprintln!("[AudioRecorder] Pre-roll stream error: {}", err);
+
+    let stream = device
+        .build_input_stream(
+            &stream_config,
+            move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                let mut ring = match buffer_clone.lock() {
+                    Ok(guard) => guard,
+                    Err(poisoned) => poisoned.into_inner(),
+                };
+
+                if channels > 1 {
+                    for chunk in data.chunks(channels) {
+                        ring.push_back(chunk.iter().sum::<f32>() / channels as f32);
+                    }
+                } else {
+                    ring.extend(data.iter().copied());
+                }
+
+                // Keep only the most recent `capacity` samples
+                let overflow = ring.len().saturating_sub(capacity);
+                if overflow > 0 {
+                    ring.drain(..overflow);
+                }
+            },
+            err_fn,
+            None,
+        )
+        .map_err(|e| AudioRecordingError::StreamInitFailed(e.to_string()))?;
+
+    stream
+        .play()
+        .map_err(|e| AudioRecordingError::StreamInitFailed(e.to_string()))?;
+
+    Ok(PreRollState {
+        buffer,
+        sample_rate,
+        device_name: device.name().ok(),
+        _stream: stream,
+    })
+}
+
+/// Name of the OS default input device, if any
+pub fn default_input_device_name() -> Option<String> {
+    cpal::default_host().default_input_device()?.name().ok()
+}
+
+/// Enumerate input devices with their default and supported configs
+pub fn list_input_devices() -> Result<Vec<AudioInputDevice>, AudioRecordingError> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|device| device.name().ok());
+
+    let devices = host
+        .input_devices()
+        .map_err(|e| AudioRecordingError::ProcessingError(e.to_string()))?;
+
+    Ok(devices
+        .filter_map(|device| {
+            let name = device.name().ok()?;
+            let default_config = device.default_input_config().ok();
+
+            let mut supported_channels: Vec<u16> = device
+                .supported_input_configs()
+                .map(|configs| configs.map(|c| c.channels()).collect())
+                .unwrap_or_default();
+            supported_channels.sort_unstable();
+            supported_channels.dedup();
+
+            Some(AudioInputDevice {
+                is_default: default_name.as_deref() == Some(name.as_str()),
+                name,
+                default_sample_rate: default_config.as_ref().map(|c| c.sample_rate().0),
+                default_channels: default_config.as_ref().map(|c| c.channels()),
+                supported_channels,
+            })
+        })
+        .collect())
+}
+
+/// Input device named `device_name`, or the system default when unset or not found
+fn select_input_device(host: &cpal::Host, device_name: Option<&str>) -> Option<cpal::Device> {
+    if let Some(wanted) = device_name {
+        let found = host
+            .input_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|device| device.name().ok().as_deref() == Some(wanted)));
+        if found.is_some() {
+            return found;
+        }
+        eprintln!("[AudioRecorder] Input device '{}' not found, using default", wanted);
+    }
+    host.default_input_device()
+}
+
+/// Pick a microphone stream config, preferring the requested sample rate and channel count
+fn microphone_stream_config(
+    device: &cpal::Device,
+    config: &AudioRecordingConfig,
+) -> Result<cpal::StreamConfig, AudioRecordingError> {
     // Get supported config - prefer our target sample rate
     let supported_config = device
         .supported_input_configs()
@@ -202,9 +519,52 @@ fn start_recording_internal(
         supported_config.max_sample_rate().0.min(48000)
     };
 
-    let stream_config = supported_config
+    Ok(supported_config
         .with_sample_rate(cpal::SampleRate(sample_rate))
+        .config())
+}
+
+/// Default output device and its config, captured through WASAPI loopback
+#[cfg(target_os = "windows")]
+fn loopback_device(host: &cpal::Host) -> Result<(cpal::Device, cpal::StreamConfig), AudioRecordingError> {
+    let device = host
+        .default_output_device()
+        .ok_or_else(|| AudioRecordingError::StreamInitFailed("No audio output device available".to_string()))?;
+    // Building an input stream on an output device opens it in loopback mode
+    let stream_config = device
+        .default_output_config()
+        .map_err(|e| AudioRecordingError::StreamInitFailed(e.to_string()))?
         .config();
+    Ok((device, stream_config))
+}
+
+/// Loopback capture needs WASAPI; macOS and Linux require a virtual device
+/// (e.g. BlackHole or a PulseAudio monitor) selected as a microphone instead
+#[cfg(not(target_os = "windows"))]
+fn loopback_device(_host: &cpal::Host) -> Result<(cpal::Device, cpal::StreamConfig), AudioRecordingError> {
+    Err(AudioRecordingError::UnsupportedSource(
+        "System audio loopback is only supported on Windows; select a loopback/monitor input device instead".to_string(),
+    ))
+}
+
+fn start_recording_internal(
+    sessions: &mut HashMap<String, RecordingState>,
+    pre_roll: Option<&PreRollState>,
+    config: AudioRecordingConfig,
+    app_handle: Option<tauri::AppHandle>,
+    command_sender: Sender<AudioCommand>,
+) -> Result<AudioRecordingSession, AudioRecordingError> {
+    // Get the requested device and stream config
+    let host = cpal::default_host();
+    let (device, stream_config) = match config.source {
+        AudioSource::Microphone => {
+            let device = select_input_device(&host, config.device_name.as_deref())
+                .ok_or(AudioRecordingError::NoInputDevice)?;
+            let stream_config = microphone_stream_config(&device, &config)?;
+            (device, stream_config)
+        }
+        AudioSource::SystemLoopback => loopback_device(&host)?,
+    };
 
     // Create session info
     let session_id = format!("rec-{}", uuid_simple());
@@ -218,10 +578,32 @@ fn start_recording_internal(
         started_at,
         sample_rate: stream_config.sample_rate.0,
         channels: stream_config.channels,
+        device_name: device.name().ok(),
+    };
+
+    // Seed the buffer with pre-roll audio when the passive stream matches the session device and rate
+    let initial_samples = match pre_roll {
+        // Pre-roll is microphone audio, which doesn't belong in a loopback capture
+        Some(_) if config.source == AudioSource::SystemLoopback => Vec::new(),
+        Some(pre_roll) if pre_roll.device_name != session.device_name => {
+            eprintln!("[AudioRecorder] Pre-roll device differs from session, skipping pre-roll");
+            Vec::new()
+        }
+        Some(pre_roll) if pre_roll.sample_rate == stream_config.sample_rate.0 => {
+            match pre_roll.buffer.lock() {
+                Ok(ring) => ring.iter().copied().collect(),
+                Err(poisoned) => poisoned.into_inner().iter().copied().collect(),
+            }
+        }
+        Some(_) => {
+            eprintln!("[AudioRecorder] Pre-roll sample rate differs from session, skipping pre-roll");
+            Vec::new()
+        }
+        None => Vec::new(),
     };
 
     // Shared buffer for samples
-    let samples_buffer: Arc<Mutex<Vec<f32>>> = Arc::new(Mutex::new(Vec::new()));
+    let samples_buffer: Arc<Mutex<Vec<f32>>> = Arc::new(Mutex::new(initial_samples));
     let samples_buffer_clone = Arc::clone(&samples_buffer);
     let channels = stream_config.channels as usize;
 
@@ -229,14 +611,47 @@ fn start_recording_internal(
     let app_handle_clone = app_handle.clone();
     let session_id_clone = session_id.clone();
     let last_emit_time = Arc::new(Mutex::new(std::time::Instant::now()));
+    // Loudest sample since the last audio-level event
+    let mut window_peak = 0.0f32;
+    let paused = Arc::new(AtomicBool::new(false));
+    let paused_clone = Arc::clone(&paused);
+    // Captured samples at which the session is finalized (buffer is mono)
+    let max_samples = config
+        .max_duration_ms
+        .map(|ms| (stream_config.sample_rate.0 as u64 * ms / 1000) as usize);
+    let mut max_duration_signaled = false;
+    let mut silence_detector = config.silence_timeout_ms.map(|timeout_ms| {
+        crate::audio::silence::SilenceDetector::new(
+            stream_config.sample_rate.0,
+            timeout_ms,
+            config
+                .silence_threshold
+                .unwrap_or(crate::audio::silence::DEFAULT_THRESHOLD_FACTOR),
+        )
+    });
 
-    // Create audio stream
-    let err_fn = |err| eprintln!("[AudioRecorder] Stream error: {}", err);
+    // Create audio stream. A lost device finalizes the session via the audio thread,
+    // since the stream itself can only be dropped there.
+    let session_id_for_err = session_id.clone();
+    let max_duration_sender = command_sender.clone();
+    let err_fn = move |err| {
+        eprintln!("[AudioRecorder] Stream error: {}", err);
+        if let cpal::StreamError::DeviceNotAvailable = err {
+            let _ = command_sender.send(AudioCommand::DeviceLost {
+                session_id: session_id_for_err.clone(),
+            });
+        }
+    };
 
     let stream = device
         .build_input_stream(
             &stream_config,
             move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                // Paused audio is dropped before it reaches levels, silence detection or the buffer
+                if paused_clone.load(Ordering::Relaxed) {
+                    return;
+                }
+
                 // Handle poisoned mutex gracefully
                 let mut buffer = match samples_buffer_clone.lock() {
                     Ok(guard) => guard,
@@ -254,15 +669,31 @@ fn start_recording_internal(
                     0.0
                 };
 
-                // Emit audio level event every ~50ms
+                window_peak = data.iter().fold(window_peak, |peak, &s| peak.max(s.abs()));
+
+                // Emit audio level event every ~50ms, with the peak over that window
                 if let Some(app) = &app_handle_clone {
                     let mut last_time = last_emit_time.lock().unwrap();
                     if last_time.elapsed().as_millis() >= 50 {
                         let _ = app.emit("audio-level", serde_json::json!({
                             "sessionId": session_id_clone,
                             "level": rms,
+                            "peak": window_peak.min(1.0),
+                            "clipping": window_peak >= CLIPPING_LEVEL,
                         }));
                         *last_time = std::time::Instant::now();
+                        window_peak = 0.0;
+                    }
+                }
+
+                // Tell the frontend to stop once the user has gone quiet
+                if let Some(detector) = silence_detector.as_mut() {
+                    if detector.process(data, channels) {
+                        if let Some(app) = &app_handle_clone {
+                            let _ = app.emit("audio-silence-detected", serde_json::json!({
+                                "sessionId": session_id_clone,
+                            }));
+                        }
                     }
                 }
 
@@ -275,6 +706,19 @@ fn start_recording_internal(
                 } else {
                     buffer.extend_from_slice(data);
                 }
+
+                // Cap the buffer and let the audio thread finalize the session
+                if let Some(max_samples) = max_samples {
+                    if buffer.len() >= max_samples {
+                        buffer.truncate(max_samples);
+                        if !max_duration_signaled {
+                            max_duration_signaled = true;
+                            let _ = max_duration_sender.send(AudioCommand::MaxDurationReached {
+                                session_id: session_id_clone.clone(),
+                            });
+                        }
+                    }
+                }
             },
             err_fn,
             None,
@@ -287,39 +731,38 @@ fn start_recording_internal(
         .map_err(|e| AudioRecordingError::StreamInitFailed(e.to_string()))?;
 
     // Store recording state
-    *active_recording = Some(RecordingState {
+    sessions.insert(session_id.clone(), RecordingState {
         session: session.clone(),
         samples: samples_buffer,
         stream,
+        app_handle,
+        min_duration_ms: config.min_duration_ms,
+        high_pass_hz: config.high_pass_hz,
+        target_peak_dbfs: config.target_peak_dbfs,
+        trim_silence: config.trim_silence,
+        output_format: config.output_format,
+        waveform_buckets: config.waveform_buckets,
+        paused,
+        active_ms: 0,
+        resumed_at: Some(std::time::Instant::now()),
     });
 
     Ok(session)
 }
 
 fn stop_recording_internal(
-    active_recording: &mut Option<RecordingState>,
+    sessions: &mut HashMap<String, RecordingState>,
     session_id: &str,
 ) -> Result<AudioRecordingResult, AudioRecordingError> {
-    let state = active_recording.take().ok_or(AudioRecordingError::NoActiveSession)?;
-
-    // Verify session ID matches
-    if state.session.session_id != session_id {
-        // Put it back
-        *active_recording = Some(state);
-        return Err(AudioRecordingError::SessionMismatch);
-    }
+    let state = take_session(sessions, session_id)?;
 
     // Stream is dropped here, stopping recording
     drop(state.stream);
 
-    let duration_ms = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
-        - state.session.started_at;
+    let mut duration_ms = state.active_duration_ms();
 
     // Get the collected samples
-    let samples = {
+    let mut samples = {
         let guard = match state.samples.lock() {
             Ok(guard) => guard,
             Err(poisoned) => {
@@ -330,66 +773,52 @@ fn stop_recording_internal(
         guard.clone()
     };
 
-    // Convert to WAV (mono output)
-    let audio_data = encode_wav(&samples, state.session.sample_rate, 1)?;
+    // Judge emptiness by captured audio, not wall-clock time
+    let captured_ms = samples.len() as u64 * 1000 / state.session.sample_rate.max(1) as u64;
+    let is_empty = captured_ms < state.min_duration_ms;
+
+    if let Some(cutoff_hz) = state.high_pass_hz {
+        crate::audio::filter::high_pass(&mut samples, state.session.sample_rate, cutoff_hz);
+    }
+
+    if state.trim_silence {
+        let range = crate::audio::silence::trim_silence_range(&samples, state.session.sample_rate);
+        samples.truncate(range.end);
+        samples.drain(..range.start);
+        duration_ms = samples.len() as u64 * 1000 / state.session.sample_rate.max(1) as u64;
+    }
+
+    // Only the encoded output is boosted; live level events were computed from the raw input
+    let applied_gain_db = state
+        .target_peak_dbfs
+        .map(|target| crate::audio::filter::normalize_peak(&mut samples, target))
+        .unwrap_or(0.0);
+
+    // Encode (mono output)
+    let audio_data = crate::audio::encode::encode(&samples, state.session.sample_rate, state.output_format)?;
+    let waveform = crate::audio::waveform::compute_waveform(&samples, state.waveform_buckets);
 
     Ok(AudioRecordingResult {
         session_id: session_id.to_string(),
         duration_ms,
         audio_data,
+        output_format: state.output_format,
         sample_rate: state.session.sample_rate,
+        is_empty,
+        applied_gain_db,
+        waveform,
     })
 }
 
 fn cancel_recording_internal(
-    active_recording: &mut Option<RecordingState>,
+    sessions: &mut HashMap<String, RecordingState>,
     session_id: &str,
 ) -> Result<(), AudioRecordingError> {
-    let state = active_recording.take().ok_or(AudioRecordingError::NoActiveSession)?;
-
-    if state.session.session_id != session_id {
-        *active_recording = Some(state);
-        return Err(AudioRecordingError::SessionMismatch);
-    }
-
+    let state = take_session(sessions, session_id)?;
     drop(state.stream);
     Ok(())
 }
 
-/// Encode samples as WAV
-fn encode_wav(
-    samples: &[f32],
-    sample_rate: u32,
-    channels: u16,
-) -> Result<Vec<u8>, AudioRecordingError> {
-    let spec = hound::WavSpec {
-        channels,
-        sample_rate,
-        bits_per_sample: 16,
-        sample_format: hound::SampleFormat::Int,
-    };
-
-    let mut cursor = std::io::Cursor::new(Vec::new());
-    {
-        let mut writer = hound::WavWriter::new(&mut cursor, spec)
-            .map_err(|e| AudioRecordingError::EncodingError(e.to_string()))?;
-
-        for &sample in samples {
-            // Convert f32 [-1.0, 1.0] to i16
-            let sample_i16 = (sample * 32767.0).clamp(-32768.0, 32767.0) as i16;
-            writer
-                .write_sample(sample_i16)
-                .map_err(|e| AudioRecordingError::EncodingError(e.to_string()))?;
-        }
-
-        writer
-            .finalize()
-            .map_err(|e| AudioRecordingError::EncodingError(e.to_string()))?;
-    }
-
-    Ok(cursor.into_inner())
-}
-
 /// Generate a simple UUID-like string
 fn uuid_simple() -> String {
     let timestamp = SystemTime::now()
@@ -0,0 +1,210 @@
+use std::f64::consts::PI;
+
+/// Zero crossings of the sinc kernel on each side; more means a sharper anti-aliasing filter
+const ZERO_CROSSINGS: usize = 16;
+/// Cutoff as a fraction of the lower Nyquist frequency, leaving room for the transition band
+const ROLLOFF: f64 = 0.95;
+/// Kernel table entries per input sample; lookups between entries are interpolated
+const TABLE_RESOLUTION: usize = 128;
+
+/// Blackman-windowed sinc low-pass kernel sampled over [0, half_width] input samples
+struct SincKernel {
+    table: Vec<f64>,
+    /// Kernel half-width in input samples
+    half_width: f64,
+}
+
+impl SincKernel {
+    /// `cutoff` is relative to the input Nyquist frequency (1.0 = no band limiting)
+    fn new(cutoff: f64) -> Self {
+        let half_width = ZERO_CROSSINGS as f64 / cutoff;
+        let len = (half_width * TABLE_RESOLUTION as f64).ceil() as usize + 2;
+        let table = (0..len)
+            .map(|i| {
+                let d = i as f64 / TABLE_RESOLUTION as f64;
+                if d > half_width {
+                    return 0.0;
+                }
+                let x = cutoff * d;
+                let sinc = if x == 0.0 { 1.0 } else { (PI * x).sin() / (PI * x) };
+                let phase = PI * d / half_width;
+                let window = 0.42 + 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos();
+                cutoff * sinc * window
+            })
+            .collect();
+        Self { table, half_width }
+    }
+
+    fn at(&self, distance: f64) -> f64 {
+        let pos = distance.abs() * TABLE_RESOLUTION as f64;
+        let idx = pos as usize;
+        if idx + 1 >= self.table.len() {
+            return 0.0;
+        }
+        let frac = pos - idx as f64;
+        self.table[idx] * (1.0 - frac) + self.table[idx + 1] * frac
+    }
+
+    /// Input samples on each side of a position that contribute to it
+    fn reach(&self) -> isize {
+        self.half_width.ceil() as isize
+    }
+
+    /// Filtered value at input position `t`, where `samples[0]` is input sample `first`
+    fn interpolate(&self, samples: &[f32], first: usize, t: f64) -> f32 {
+        let center = t.floor() as isize;
+        let reach = self.reach();
+        let first = first as isize;
+        let last = first + samples.len() as isize - 1;
+
+        let mut acc = 0.0;
+        let mut weight_sum = 0.0;
+        for k in (center - reach + 1).max(first)..=(center + reach).min(last) {
+            let weight = self.at(t - k as f64);
+            acc += samples[(k - first) as usize] as f64 * weight;
+            weight_sum += weight;
+        }
+
+        // Normalizing keeps unity gain, including near the edges where the kernel is cut off
+        if weight_sum.abs() > f64::EPSILON { (acc / weight_sum) as f32 } else { 0.0 }
+    }
+}
+
+/// Resample a mono signal from `from_rate` to `to_rate` with a windowed-sinc filter.
+/// When downsampling the kernel is widened to low-pass below the target Nyquist frequency,
+/// so content that would alias (e.g. sibilants above 8 kHz going to 16 kHz) is removed
+/// instead of folding back into the speech band. Works for arbitrary rate pairs.
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() || from_rate == 0 || to_rate == 0 {
+        return samples.to_vec();
+    }
+
+    let ratio = to_rate as f64 / from_rate as f64;
+    let kernel = SincKernel::new(ratio.min(1.0) * ROLLOFF);
+    let new_len = (samples.len() as f64 * ratio) as usize;
+
+    // Each output sample sits at i / ratio in input-sample units
+    (0..new_len)
+        .map(|i| kernel.interpolate(samples, 0, i as f64 / ratio))
+        .collect()
+}
+
+/// `resample` for a signal that arrives in chunks (e.g. a live recording). The output matches
+/// resampling the whole signal at once: input the filter still needs is carried over to the next
+/// chunk, and output positions follow one timeline, so there are no clicks at chunk edges and
+/// no drift from per-chunk rounding. Output lags the input by the kernel half-width
+/// (about 1 ms at 16 kHz) until `finish`.
+pub struct StreamResampler {
+    /// None when the rates match and samples pass through unchanged
+    kernel: Option<SincKernel>,
+    ratio: f64,
+    /// Input not yet consumed by the filter; `buffer[0]` is input sample `buffer_start`
+    buffer: Vec<f32>,
+    buffer_start: usize,
+    /// Index of the next output sample
+    next_output: usize,
+}
+
+impl StreamResampler {
+    pub fn new(from_rate: u32, to_rate: u32) -> Self {
+        let passthrough = from_rate == to_rate || from_rate == 0 || to_rate == 0;
+        let ratio = if passthrough { 1.0 } else { to_rate as f64 / from_rate as f64 };
+        Self {
+            kernel: (!passthrough).then(|| SincKernel::new(ratio.min(1.0) * ROLLOFF)),
+            ratio,
+            buffer: Vec::new(),
+            buffer_start: 0,
+            next_output: 0,
+        }
+    }
+
+    /// Add the next chunk of input and return the output it completes
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        self.buffer.extend_from_slice(samples);
+        self.drain(false)
+    }
+
+    /// Return the output still held back at the end of the signal
+    pub fn finish(&mut self) -> Vec<f32> {
+        self.drain(true)
+    }
+
+    fn drain(&mut self, at_end: bool) -> Vec<f32> {
+        let Some(kernel) = &self.kernel else {
+            return std::mem::take(&mut self.buffer);
+        };
+        let reach = kernel.reach();
+        let received = self.buffer_start + self.buffer.len();
+        let total_output = (received as f64 * self.ratio) as usize;
+
+        let mut output = Vec::new();
+        while self.next_output < total_output {
+            let t = self.next_output as f64 / self.ratio;
+            // Wait for every input sample the kernel covers, unless none are coming
+            if !at_end && t.floor() as isize + reach >= received as isize {
+                break;
+            }
+            output.push(kernel.interpolate(&self.buffer, self.buffer_start, t));
+            self.next_output += 1;
+        }
+
+        // Keep only what the next output sample can still reach
+        let next_t = self.next_output as f64 / self.ratio;
+        let needed_from = (next_t.floor() as isize - reach + 1).max(0) as usize;
+        let consumed = needed_from.saturating_sub(self.buffer_start).min(self.buffer.len());
+        self.buffer.drain(..consumed);
+        self.buffer_start += consumed;
+
+        output
+    }
+}
+
+/// Resample interleaved multi-channel audio, processing each channel independently
+pub fn resample_interleaved(samples: &[f32], channels: u16, from_rate: u32, to_rate: u32) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    if channels == 1 {
+        return resample(samples, from_rate, to_rate);
+    }
+
+    let per_channel: Vec<Vec<f32>> = (0..channels)
+        .map(|ch| {
+            let channel: Vec<f32> = samples.iter().skip(ch).step_by(channels).copied().collect();
+            resample(&channel, from_rate, to_rate)
+        })
+        .collect();
+
+    let frames = per_channel.iter().map(Vec::len).min().unwrap_or(0);
+    let mut interleaved = Vec::with_capacity(frames * channels);
+    for frame in 0..frames {
+        for channel in &per_channel {
+            interleaved.push(channel[frame]);
+        }
+    }
+    interleaved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_resampler_matches_whole_signal() {
+        let input: Vec<f32> = (0..44_100)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 44_100.0).sin())
+            .collect();
+        let expected = resample(&input, 44_100, 24_000);
+
+        let mut resampler = StreamResampler::new(44_100, 24_000);
+        // Uneven chunks, like the ~100 ms reads from a live tap
+        let mut streamed = Vec::new();
+        for chunk in input.chunks(4_411) {
+            streamed.extend(resampler.process(chunk));
+        }
+        streamed.extend(resampler.finish());
+
+        assert_eq!(streamed.len(), expected.len());
+        for (a, b) in streamed.iter().zip(&expected) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+/// Length of the ambient noise calibration at the start of a session
+const CALIBRATION_MS: u64 = 300;
+/// Lowest speech threshold, so a near-silent calibration doesn't make every breath count as speech
+const MIN_THRESHOLD: f32 = 0.005;
+/// Default multiple of the ambient noise level that counts as speech
+pub const DEFAULT_THRESHOLD_FACTOR: f32 = 2.5;
+
+/// Detects a run of silence after speech, using an RMS threshold relative to the
+/// ambient noise measured during the first `CALIBRATION_MS` of the session
+pub struct SilenceDetector {
+    sample_rate: u32,
+    timeout_ms: u64,
+    threshold_factor: f32,
+    calibration_frames: u64,
+    calibration_sum_squares: f64,
+    calibration_samples: u64,
+    threshold: Option<f32>,
+    heard_speech: bool,
+    silent_frames: u64,
+    fired: bool,
+}
+
+impl SilenceDetector {
+    pub fn new(sample_rate: u32, timeout_ms: u64, threshold_factor: f32) -> Self {
+        Self {
+            sample_rate: sample_rate.max(1),
+            timeout_ms,
+            threshold_factor,
+            calibration_frames: 0,
+            calibration_sum_squares: 0.0,
+            calibration_samples: 0,
+            threshold: None,
+            heard_speech: false,
+            silent_frames: 0,
+            fired: false,
+        }
+    }
+
+    fn frames_to_ms(&self, frames: u64) -> u64 {
+        frames * 1000 / self.sample_rate as u64
+    }
+
+    /// Feed one callback's worth of interleaved samples. Returns true exactly once,
+    /// when silence following speech has lasted longer than the timeout.
+    pub fn process(&mut self, data: &[f32], channels: usize) -> bool {
+        if self.fired || data.is_empty() {
+            return false;
+        }
+        let frames = (data.len() / channels.max(1)) as u64;
+
+        let Some(threshold) = self.threshold else {
+            self.calibration_frames += frames;
+            self.calibration_sum_squares += data.iter().map(|&s| (s * s) as f64).sum::<f64>();
+            self.calibration_samples += data.len() as u64;
+            if self.frames_to_ms(self.calibration_frames) >= CALIBRATION_MS {
+                let ambient = (self.calibration_sum_squares / self.calibration_samples.max(1) as f64).sqrt() as f32;
+                self.threshold = Some((ambient * self.threshold_factor).max(MIN_THRESHOLD));
+            }
+            return false;
+        };
+
+        let rms = (data.iter().map(|&s| s * s).sum::<f32>() / data.len() as f32).sqrt();
+        if rms >= threshold {
+            self.heard_speech = true;
+            self.silent_frames = 0;
+            return false;
+        }
+
+        // Leading silence before the user starts talking doesn't count
+        if !self.heard_speech {
+            return false;
+        }
+
+        self.silent_frames += frames;
+        if self.frames_to_ms(self.silent_frames) >= self.timeout_ms {
+            self.fired = true;
+            return true;
+        }
+        false
+    }
+}
+
+/// Window used when scanning for leading/trailing silence
+const TRIM_WINDOW_MS: u64 = 10;
+/// Audio kept around the detected speech so word onsets and tails aren't clipped
+const TRIM_PADDING_MS: u64 = 100;
+/// Windows quieter than this fraction of the loudest window count as silence (about -26 dB)
+const TRIM_RELATIVE_THRESHOLD: f32 = 0.05;
+
+/// Range of `samples` (mono) left after trimming leading and trailing silence, with padding.
+/// Returns the whole range when no window stands out from the rest.
+pub fn trim_silence_range(samples: &[f32], sample_rate: u32) -> std::ops::Range<usize> {
+    let window = ((sample_rate as u64 * TRIM_WINDOW_MS / 1000) as usize).max(1);
+    let rms: Vec<f32> = samples
+        .chunks(window)
+        .map(|chunk| (chunk.iter().map(|&s| s * s).sum::<f32>() / chunk.len() as f32).sqrt())
+        .collect();
+
+    let loudest = rms.iter().copied().fold(0.0f32, f32::max);
+    let threshold = (loudest * TRIM_RELATIVE_THRESHOLD).max(MIN_THRESHOLD);
+    let (Some(first), Some(last)) = (
+        rms.iter().position(|&level| level >= threshold),
+        rms.iter().rposition(|&level| level >= threshold),
+    ) else {
+        return 0..samples.len();
+    };
+
+    let padding = (sample_rate as u64 * TRIM_PADDING_MS / 1000) as usize;
+    let start = (first * window).saturating_sub(padding);
+    let end = ((last + 1) * window + padding).min(samples.len());
+    start..end
+}
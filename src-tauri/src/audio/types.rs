@@ -1,5 +1,28 @@
 use serde::{Deserialize, Serialize};
 
+/// What a recording captures
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioSource {
+    #[default]
+    Microphone,
+    /// Audio playing through the default output device (Windows/WASAPI only)
+    SystemLoopback,
+}
+
+/// Container/codec of the audio returned when a recording stops
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioOutputFormat {
+    /// 16-bit PCM; required for local whisper models
+    #[default]
+    Wav,
+    /// Lossless, roughly half the size of WAV
+    Flac,
+    /// Ogg Opus at speech bitrate, smallest upload for cloud transcription
+    Opus,
+}
+
 /// Configuration for audio recording
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -14,6 +37,34 @@ pub struct AudioRecordingConfig {
     pub noise_suppression: bool,
     /// Enable automatic gain control
     pub auto_gain_control: bool,
+    /// Recordings with less captured audio than this are flagged `is_empty` (default: 300)
+    pub min_duration_ms: u64,
+    /// High-pass cutoff in Hz applied before encoding to remove low-frequency rumble
+    /// (e.g. 80). Off by default.
+    pub high_pass_hz: Option<f32>,
+    /// Input device to record from, as returned by `list_audio_input_devices`.
+    /// Falls back to the system default when unset or not found.
+    pub device_name: Option<String>,
+    /// Emit `audio-silence-detected` once the user has been silent this long after speaking.
+    /// Off by default.
+    pub silence_timeout_ms: Option<u64>,
+    /// Speech threshold as a multiple of the ambient noise level calibrated at the start
+    /// of the session (default: 2.5)
+    pub silence_threshold: Option<f32>,
+    /// Finalize the recording automatically once this much audio has been captured,
+    /// emitting `recording-max-duration-reached` with the result. Off by default.
+    pub max_duration_ms: Option<u64>,
+    /// Microphone (default) or system audio loopback; `device_name` applies to microphones only
+    pub source: AudioSource,
+    /// Boost quiet recordings so their peak reaches this level (e.g. -3.0) before encoding.
+    /// Louder recordings are left untouched. Off by default.
+    pub target_peak_dbfs: Option<f32>,
+    /// Cut leading and trailing silence (keeping ~100ms of padding) before encoding
+    pub trim_silence: bool,
+    /// Encoding of the returned audio (default: WAV)
+    pub output_format: AudioOutputFormat,
+    /// Number of waveform buckets returned with the result (default: 200; 0 skips the waveform)
+    pub waveform_buckets: usize,
 }
 
 impl Default for AudioRecordingConfig {
@@ -24,6 +75,17 @@ impl Default for AudioRecordingConfig {
             echo_cancellation: true,
             noise_suppression: true,
             auto_gain_control: true,
+            min_duration_ms: 300,
+            high_pass_hz: None,
+            device_name: None,
+            silence_timeout_ms: None,
+            silence_threshold: None,
+            max_duration_ms: None,
+            source: AudioSource::Microphone,
+            target_peak_dbfs: None,
+            trim_silence: false,
+            output_format: AudioOutputFormat::Wav,
+            waveform_buckets: 200,
         }
     }
 }
@@ -39,6 +101,22 @@ pub struct AudioRecordingSession {
     pub sample_rate: u32,
     /// Number of channels
     pub channels: u16,
+    /// Name of the input device captured from, to detect OS default-device switches
+    pub device_name: Option<String>,
+}
+
+/// An available audio input device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioInputDevice {
+    pub name: String,
+    /// True for the current system default input device
+    pub is_default: bool,
+    /// Sample rate of the device's default input config
+    pub default_sample_rate: Option<u32>,
+    /// Channel count of the device's default input config
+    pub default_channels: Option<u16>,
+    /// Distinct channel counts across all supported input configs
+    pub supported_channels: Vec<u16>,
 }
 
 /// Result of a completed recording
@@ -46,12 +124,31 @@ pub struct AudioRecordingSession {
 pub struct AudioRecordingResult {
     /// Session identifier
     pub session_id: String,
-    /// Duration of recording in milliseconds
+    /// Duration of recording in milliseconds, excluding paused time; with `trim_silence` this is
+    /// the length of the trimmed output
     pub duration_ms: u64,
-    /// WAV audio data as bytes
+    /// Encoded audio data as bytes, in `output_format`
     pub audio_data: Vec<u8>,
+    /// Encoding of `audio_data`
+    pub output_format: AudioOutputFormat,
     /// Sample rate of the audio
     pub sample_rate: u32,
+    /// True when less audio than `min_duration_ms` was captured (e.g. an accidental tap);
+    /// the frontend should skip transcription
+    pub is_empty: bool,
+    /// Gain applied by `target_peak_dbfs` normalization, in dB (0 when none was applied)
+    pub applied_gain_db: f32,
+    /// Peak/RMS envelope of the returned audio in `waveform_buckets` buckets, for previews
+    pub waveform: Vec<crate::audio::waveform::WaveformBucket>,
+}
+
+/// Result of trimming a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioTrimResult {
+    /// Trimmed WAV audio data as bytes
+    pub audio_data: Vec<u8>,
+    /// Duration of the trimmed clip in milliseconds
+    pub duration_ms: u64,
 }
 
 /// Error types for audio recording
@@ -69,6 +166,8 @@ pub enum AudioRecordingError {
     ProcessingError(String),
     /// WAV encoding error
     EncodingError(String),
+    /// Requested audio source isn't available on this platform
+    UnsupportedSource(String),
 }
 
 impl std::fmt::Display for AudioRecordingError {
@@ -80,6 +179,7 @@ impl std::fmt::Display for AudioRecordingError {
             Self::SessionMismatch => write!(f, "Session ID does not match active recording"),
             Self::ProcessingError(msg) => write!(f, "Audio processing error: {}", msg),
             Self::EncodingError(msg) => write!(f, "WAV encoding error: {}", msg),
+            Self::UnsupportedSource(msg) => write!(f, "Unsupported audio source: {}", msg),
         }
     }
 }
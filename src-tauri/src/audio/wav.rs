@@ -0,0 +1,114 @@
+use crate::audio::types::AudioRecordingError;
+
+/// Decoded PCM audio: interleaved f32 samples in [-1.0, 1.0]
+pub struct DecodedAudio {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl DecodedAudio {
+    /// Downmix to mono by averaging channels
+    pub fn to_mono(&self) -> Vec<f32> {
+        if self.channels > 1 {
+            self.samples
+                .chunks(self.channels as usize)
+                .map(|chunk| chunk.iter().sum::<f32>() / self.channels as f32)
+                .collect()
+        } else {
+            self.samples.clone()
+        }
+    }
+}
+
+/// Decode WAV bytes (int or float PCM) into f32 samples
+pub fn decode_wav(wav_data: &[u8]) -> Result<DecodedAudio, AudioRecordingError> {
+    let cursor = std::io::Cursor::new(wav_data);
+    let reader = hound::WavReader::new(cursor)
+        .map_err(|e| AudioRecordingError::ProcessingError(format!("Failed to parse WAV: {}", e)))?;
+
+    let spec = reader.spec();
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .into_samples::<f32>()
+            .filter_map(|s| s.ok())
+            .collect(),
+        hound::SampleFormat::Int => {
+            let max_val = (1u32 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .into_samples::<i32>()
+                .filter_map(|s| s.ok())
+                .map(|s| s as f32 / max_val)
+                .collect()
+        }
+    };
+
+    Ok(DecodedAudio {
+        samples,
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+    })
+}
+
+/// Encode interleaved f32 samples as 16-bit PCM WAV
+pub fn encode_wav(
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+) -> Result<Vec<u8>, AudioRecordingError> {
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut cursor = std::io::Cursor::new(Vec::new());
+    {
+        let mut writer = hound::WavWriter::new(&mut cursor, spec)
+            .map_err(|e| AudioRecordingError::EncodingError(e.to_string()))?;
+
+        for &sample in samples {
+            // Convert f32 [-1.0, 1.0] to i16
+            let sample_i16 = (sample * 32767.0).clamp(-32768.0, 32767.0) as i16;
+            writer
+                .write_sample(sample_i16)
+                .map_err(|e| AudioRecordingError::EncodingError(e.to_string()))?;
+        }
+
+        writer
+            .finalize()
+            .map_err(|e| AudioRecordingError::EncodingError(e.to_string()))?;
+    }
+
+    Ok(cursor.into_inner())
+}
+
+/// Cut interleaved audio to the [start_ms, end_ms) range
+pub fn trim(audio: &DecodedAudio, start_ms: u64, end_ms: u64) -> Result<DecodedAudio, AudioRecordingError> {
+    let channels = audio.channels.max(1) as usize;
+    let frames = audio.samples.len() / channels;
+    let length_ms = frames as u64 * 1000 / audio.sample_rate.max(1) as u64;
+
+    if start_ms >= end_ms {
+        return Err(AudioRecordingError::ProcessingError(format!(
+            "Invalid trim range: start ({} ms) must be before end ({} ms)",
+            start_ms, end_ms
+        )));
+    }
+    if end_ms > length_ms {
+        return Err(AudioRecordingError::ProcessingError(format!(
+            "Invalid trim range: end ({} ms) exceeds clip length ({} ms)",
+            end_ms, length_ms
+        )));
+    }
+
+    let to_frame = |ms: u64| (ms * audio.sample_rate as u64 / 1000) as usize;
+    let (start_frame, end_frame) = (to_frame(start_ms), to_frame(end_ms).min(frames));
+
+    Ok(DecodedAudio {
+        samples: audio.samples[start_frame * channels..end_frame * channels].to_vec(),
+        sample_rate: audio.sample_rate,
+        channels: audio.channels,
+    })
+}
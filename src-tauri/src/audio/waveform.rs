@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+/// Summary of one slice of a clip for waveform rendering
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaveformBucket {
+    /// Largest absolute sample in the slice, 0-1
+    pub peak: f32,
+    /// Root-mean-square level of the slice, 0-1
+    pub rms: f32,
+}
+
+/// Summarize mono samples into `buckets` peak/RMS pairs, scaled so the loudest peak in the
+/// clip is 1.0. Clips with fewer samples than `buckets` get one bucket per sample.
+pub fn compute_waveform(samples: &[f32], buckets: usize) -> Vec<WaveformBucket> {
+    if samples.is_empty() || buckets == 0 {
+        return Vec::new();
+    }
+
+    let len = samples.len();
+    let buckets = buckets.min(len);
+    // Bucket sizes differ by at most one sample, so exactly `buckets` come back
+    let mut waveform: Vec<WaveformBucket> = (0..buckets)
+        .map(|i| &samples[i * len / buckets..(i + 1) * len / buckets])
+        .map(|chunk| {
+            let peak = chunk.iter().fold(0.0f32, |max, s| max.max(s.abs()));
+            let rms = (chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32).sqrt();
+            WaveformBucket { peak, rms }
+        })
+        .collect();
+
+    let max_peak = waveform.iter().fold(0.0f32, |max, b| max.max(b.peak));
+    if max_peak > 0.0 {
+        for bucket in &mut waveform {
+            bucket.peak = (bucket.peak / max_peak).min(1.0);
+            bucket.rms = (bucket.rms / max_peak).min(1.0);
+        }
+    }
+
+    waveform
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn returns_requested_bucket_count() {
+        let samples = vec![0.5f32; 1000];
+        assert_eq!(compute_waveform(&samples, 300).len(), 300);
+        assert_eq!(compute_waveform(&samples, 1000).len(), 1000);
+        assert_eq!(compute_waveform(&samples, 5000).len(), 1000);
+    }
+}
@@ -0,0 +1,135 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use crate::audio::AudioRecordingResult;
+use crate::commands::{AppState, DictationState};
+
+const DICTATION_HOTKEY_EVENT: &str = "dictation-hotkey";
+
+/// Emitted on `dictation-hotkey` each time the hotkey toggles recording
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum DictationHotkeyEvent {
+    Started { session_id: String },
+    Stopped { session_id: String, result: AudioRecordingResult },
+    Failed { error: String },
+}
+
+struct DictationHotkey {
+    shortcut: Option<Shortcut>,
+    /// Recording started by the hotkey, stopped on the next press
+    session_id: Option<String>,
+}
+
+static DICTATION_HOTKEY: Mutex<DictationHotkey> = Mutex::new(DictationHotkey {
+    shortcut: None,
+    session_id: None,
+});
+
+fn lock() -> std::sync::MutexGuard<'static, DictationHotkey> {
+    match DICTATION_HOTKEY.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Bind `accelerator` (e.g. "CommandOrControl+Shift+Space"), replacing any previous dictation hotkey.
+/// If the new one can't be bound, the previous binding is restored.
+pub fn register(app: &AppHandle, accelerator: &str) -> Result<(), String> {
+    let shortcut: Shortcut = accelerator
+        .parse()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", accelerator, e))?;
+
+    let mut state = lock();
+    if state.shortcut == Some(shortcut) {
+        return Ok(());
+    }
+
+    let previous = state.shortcut.take();
+    if let Some(previous) = previous {
+        app.global_shortcut()
+            .unregister(previous)
+            .map_err(|e| format!("Failed to unregister previous shortcut: {}", e))?;
+    }
+
+    if let Err(e) = bind(app, shortcut) {
+        if let Some(previous) = previous {
+            if bind(app, previous).is_ok() {
+                state.shortcut = Some(previous);
+            }
+        }
+        return Err(format!(
+            "Could not register '{}' (it may be in use by another application): {}",
+            accelerator, e
+        ));
+    }
+
+    state.shortcut = Some(shortcut);
+    Ok(())
+}
+
+/// Remove the dictation hotkey. A recording it started keeps running until stopped explicitly.
+pub fn unregister(app: &AppHandle) -> Result<(), String> {
+    let mut state = lock();
+    if let Some(shortcut) = state.shortcut.take() {
+        app.global_shortcut()
+            .unregister(shortcut)
+            .map_err(|e| format!("Failed to unregister shortcut: {}", e))?;
+    }
+    state.session_id = None;
+    Ok(())
+}
+
+fn bind(app: &AppHandle, shortcut: Shortcut) -> Result<(), tauri_plugin_global_shortcut::Error> {
+    app.global_shortcut().on_shortcut(shortcut, |app, _shortcut, event| {
+        if event.state() == ShortcutState::Pressed {
+            let app = app.clone();
+            // Stopping encodes the recording; keep that off the event loop
+            tauri::async_runtime::spawn_blocking(move || toggle_recording(&app));
+        }
+    })
+}
+
+fn toggle_recording(app: &AppHandle) {
+    let audio_manager = app.state::<AppState>().audio_manager.clone();
+    // Held until the new session is recorded, so two quick presses can't both see no session
+    // and start two recordings (the second press waits for the first to finish)
+    let mut state = lock();
+    let active_session = state.session_id.take();
+
+    let event = match active_session {
+        // The session can already be gone (e.g. finalized after the device was lost);
+        // the press then starts a new recording
+        Some(session_id) => match audio_manager.stop_recording(&session_id) {
+            Ok(result) => Some(DictationHotkeyEvent::Stopped { session_id, result }),
+            Err(e) => {
+                eprintln!("[Hotkey] Failed to stop dictation session {}: {}", session_id, e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let event = event.unwrap_or_else(|| {
+        let config = crate::settings::load(app, crate::settings::RECORDING_DEFAULTS_KEY);
+        match audio_manager.start_recording(config, Some(app.clone())) {
+            Ok(session) => {
+                state.session_id = Some(session.session_id.clone());
+                crate::commands::emit_dictation_state(
+                    app,
+                    DictationState::Recording {
+                        session_id: session.session_id.clone(),
+                    },
+                );
+                DictationHotkeyEvent::Started { session_id: session.session_id }
+            }
+            Err(e) => DictationHotkeyEvent::Failed { error: e.to_string() },
+        }
+    });
+    drop(state);
+
+    if let Err(e) = app.emit(DICTATION_HOTKEY_EVENT, &event) {
+        eprintln!("[Hotkey] Failed to emit {}: {}", DICTATION_HOTKEY_EVENT, e);
+    }
+}
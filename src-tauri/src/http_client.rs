@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+/// Proxy URL schemes reqwest can route through (socks5h resolves DNS on the proxy)
+const PROXY_SCHEMES: [&str; 4] = ["http", "https", "socks5", "socks5h"];
+
+/// TLS options for self-hosted / on-prem endpoints. Both are opt-in security tradeoffs:
+/// a custom CA trusts everything that CA signs, and `danger_accept_invalid_certs`
+/// disables certificate verification entirely (anyone on the network can impersonate
+/// the endpoint). Prefer `ca_cert_path` whenever the gateway's CA is available.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsSettings {
+    /// Path to a PEM file with an extra root certificate to trust
+    pub ca_cert_path: Option<String>,
+    /// Skip certificate verification for all HTTPS requests
+    pub danger_accept_invalid_certs: bool,
+}
+
+struct ActiveTls {
+    settings: TlsSettings,
+    ca_cert: Option<reqwest::Certificate>,
+}
+
+static ACTIVE_TLS: RwLock<Option<ActiveTls>> = RwLock::new(None);
+/// Explicit proxy for every request; when unset, reqwest uses HTTP_PROXY/HTTPS_PROXY/ALL_PROXY
+static ACTIVE_PROXY: RwLock<Option<reqwest::Proxy>> = RwLock::new(None);
+
+/// Validate and apply TLS settings to every HTTP client built afterwards
+pub fn set_tls_settings(settings: TlsSettings) -> Result<(), String> {
+    let ca_cert = match &settings.ca_cert_path {
+        Some(path) => {
+            let pem = std::fs::read(path)
+                .map_err(|e| format!("Failed to read CA certificate {}: {}", path, e))?;
+            let cert = reqwest::Certificate::from_pem(&pem)
+                .map_err(|e| format!("Invalid PEM certificate {}: {}", path, e))?;
+            Some(cert)
+        }
+        None => None,
+    };
+
+    if settings.danger_accept_invalid_certs {
+        eprintln!("[HTTP] WARNING: TLS certificate verification is DISABLED for all provider requests");
+    }
+
+    let active = ActiveTls { settings, ca_cert };
+    match ACTIVE_TLS.write() {
+        Ok(mut guard) => *guard = Some(active),
+        Err(poisoned) => *poisoned.into_inner() = Some(active),
+    }
+    Ok(())
+}
+
+/// Currently applied TLS settings
+pub fn get_tls_settings() -> TlsSettings {
+    let guard = match ACTIVE_TLS.read() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    guard.as_ref().map(|active| active.settings.clone()).unwrap_or_default()
+}
+
+/// Validate and apply a proxy (`http://`, `https://`, `socks5://`, optionally with
+/// `user:password@` for basic auth) to every HTTP client built afterwards.
+/// `None` goes back to the proxy environment variables.
+pub fn set_network_proxy(url: Option<&str>) -> Result<(), String> {
+    let proxy = match url {
+        Some(url) => {
+            let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+            if !PROXY_SCHEMES.contains(&parsed.scheme()) {
+                return Err(format!(
+                    "Unsupported proxy scheme '{}'; use http, https, socks5 or socks5h",
+                    parsed.scheme()
+                ));
+            }
+            let host = parsed.host_str().ok_or("Proxy URL has no host")?;
+            // Credentials in the URL are picked up by reqwest; keep them out of the log
+            eprintln!(
+                "[HTTP] Using proxy {}://{}:{}",
+                parsed.scheme(),
+                host,
+                parsed.port_or_known_default().unwrap_or(0)
+            );
+            Some(reqwest::Proxy::all(url).map_err(|e| format!("Invalid proxy URL: {}", e))?)
+        }
+        None => None,
+    };
+
+    match ACTIVE_PROXY.write() {
+        Ok(mut guard) => *guard = proxy,
+        Err(poisoned) => *poisoned.into_inner() = proxy,
+    }
+    Ok(())
+}
+
+/// reqwest client builder with the configured TLS and proxy settings applied.
+/// All outgoing HTTP clients should start from here.
+pub fn client_builder() -> reqwest::ClientBuilder {
+    let mut builder = reqwest::Client::builder();
+
+    let guard = match ACTIVE_TLS.read() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if let Some(active) = guard.as_ref() {
+        if let Some(cert) = &active.ca_cert {
+            builder = builder.add_root_certificate(cert.clone());
+        }
+        if active.settings.danger_accept_invalid_certs {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+    }
+
+    let proxy = match ACTIVE_PROXY.read() {
+        Ok(guard) => guard.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    };
+    // An explicit proxy replaces the environment variables
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(proxy);
+    }
+
+    builder
+}
@@ -0,0 +1,154 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+use serde::{Deserialize, Serialize};
+use whisper_rs::WhisperContext;
+use crate::local_models::catalog::get_model_catalog;
+use crate::local_models::whisper::WhisperBackend;
+
+/// Default memory budget for cached whisper contexts
+const DEFAULT_CACHE_BUDGET_MB: u64 = 4096;
+
+struct CachedContext {
+    model_path: PathBuf,
+    /// The same model can be cached once per backend
+    backend: WhisperBackend,
+    context: Arc<WhisperContext>,
+    /// Approximated by the model file size (weights dominate a context's memory)
+    bytes: u64,
+    last_used: Instant,
+}
+
+struct ContextCache {
+    /// Ordered from least to most recently used
+    entries: Vec<CachedContext>,
+    budget_bytes: u64,
+}
+
+static CACHE: Mutex<ContextCache> = Mutex::new(ContextCache {
+    entries: Vec::new(),
+    budget_bytes: DEFAULT_CACHE_BUDGET_MB * 1024 * 1024,
+});
+
+/// A model currently held in the context cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedModelInfo {
+    /// Catalog id, if the file belongs to a catalog model
+    pub model_id: Option<String>,
+    pub model_path: String,
+    pub backend: WhisperBackend,
+    pub bytes: u64,
+    pub idle_ms: u64,
+}
+
+/// Snapshot of the context cache
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhisperCacheStatus {
+    pub models: Vec<CachedModelInfo>,
+    pub total_bytes: u64,
+    pub budget_bytes: u64,
+}
+
+fn lock_cache() -> MutexGuard<'static, ContextCache> {
+    match CACHE.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+impl ContextCache {
+    fn total_bytes(&self) -> u64 {
+        self.entries.iter().map(|entry| entry.bytes).sum()
+    }
+
+    /// Drop least recently used contexts until the cache fits its budget.
+    /// Contexts still in use by a running transcription are freed when it finishes.
+    fn evict_to_budget(&mut self) {
+        while self.total_bytes() > self.budget_bytes && !self.entries.is_empty() {
+            let evicted = self.entries.remove(0);
+            eprintln!("[WhisperCache] Evicted {}", evicted.model_path.display());
+        }
+    }
+}
+
+fn model_id_for(model_path: &Path) -> Option<String> {
+    let filename = model_path.file_name()?.to_str()?;
+    get_model_catalog(None)
+        .into_iter()
+        .find(|entry| entry.filename == filename)
+        .map(|entry| entry.id)
+}
+
+/// Return the cached context for `model_path` on `backend`, marking it most recently used
+pub fn get(model_path: &Path, backend: WhisperBackend) -> Option<Arc<WhisperContext>> {
+    let mut cache = lock_cache();
+    let index = cache
+        .entries
+        .iter()
+        .position(|entry| entry.model_path == model_path && entry.backend == backend)?;
+    let mut entry = cache.entries.remove(index);
+    entry.last_used = Instant::now();
+    let context = Arc::clone(&entry.context);
+    cache.entries.push(entry);
+    Some(context)
+}
+
+/// Add a freshly loaded context, evicting older ones to stay within budget.
+/// A model larger than the whole budget is not cached.
+pub fn insert(model_path: &Path, backend: WhisperBackend, context: Arc<WhisperContext>) {
+    let bytes = std::fs::metadata(model_path).map(|m| m.len()).unwrap_or(0);
+    let mut cache = lock_cache();
+    if bytes > cache.budget_bytes {
+        return;
+    }
+
+    cache.entries.retain(|entry| entry.model_path != model_path || entry.backend != backend);
+    cache.entries.push(CachedContext {
+        model_path: model_path.to_path_buf(),
+        backend,
+        context,
+        bytes,
+        last_used: Instant::now(),
+    });
+    cache.evict_to_budget();
+}
+
+/// Change the memory budget, evicting immediately if the cache no longer fits
+pub fn set_budget_mb(budget_mb: u64) {
+    let mut cache = lock_cache();
+    cache.budget_bytes = budget_mb * 1024 * 1024;
+    cache.evict_to_budget();
+}
+
+pub fn status() -> WhisperCacheStatus {
+    let cache = lock_cache();
+    WhisperCacheStatus {
+        models: cache
+            .entries
+            .iter()
+            .rev()
+            .map(|entry| CachedModelInfo {
+                model_id: model_id_for(&entry.model_path),
+                model_path: entry.model_path.to_string_lossy().to_string(),
+                backend: entry.backend,
+                bytes: entry.bytes,
+                idle_ms: entry.last_used.elapsed().as_millis() as u64,
+            })
+            .collect(),
+        total_bytes: cache.total_bytes(),
+        budget_bytes: cache.budget_bytes,
+    }
+}
+
+/// Drop every cached context of `model_path` (on any backend); returns whether one was cached
+pub fn evict(model_path: &Path) -> bool {
+    let mut cache = lock_cache();
+    let before = cache.entries.len();
+    cache.entries.retain(|entry| entry.model_path != model_path);
+    cache.entries.len() != before
+}
+
+/// Drop every cached context
+pub fn clear() {
+    lock_cache().entries.clear();
+}
@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use std::sync::RwLock;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
@@ -18,6 +21,9 @@ pub struct LocalModelCatalogEntry {
     pub speed_rating: u8,
     pub accuracy_rating: u8,
     pub language_support: String,
+    /// Hex SHA-256 of the model file, checked after download (remote entries always have one)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
 }
 
 struct CatalogDef {
@@ -96,24 +102,350 @@ const CATALOG_DEFS: &[CatalogDef] = &[
     },
 ];
 
-pub fn get_model_catalog() -> Vec<LocalModelCatalogEntry> {
+/// Translated display text for a catalog entry. Ids and URLs stay locale-independent.
+struct LocalizedText {
+    locale: &'static str,
+    id: &'static str,
+    name: Option<&'static str>,
+    description: &'static str,
+}
+
+const LOCALIZED_TEXT: &[LocalizedText] = &[
+    LocalizedText {
+        locale: "pl",
+        id: "whisper-tiny",
+        name: None,
+        description: "Najszybszy, najniższa dokładność (~75 MB). Dobry do szybkich testów.",
+    },
+    LocalizedText {
+        locale: "pl",
+        id: "whisper-base",
+        name: None,
+        description: "Szybki, z przyzwoitą dokładnością (~142 MB).",
+    },
+    LocalizedText {
+        locale: "pl",
+        id: "whisper-small",
+        name: None,
+        description: "Dobry kompromis między szybkością a dokładnością (~466 MB).",
+    },
+    LocalizedText {
+        locale: "pl",
+        id: "whisper-medium",
+        name: None,
+        description: "Wysoka dokładność, wolniejszy (~1.5 GB). Polecany dla większości użytkowników.",
+    },
+    LocalizedText {
+        locale: "pl",
+        id: "whisper-large-v3-turbo",
+        name: None,
+        description: "Najlepsza dokładność przy szybkości Turbo (~1.6 GB). Najwyższa jakość.",
+    },
+];
+
+/// Find translated text for a model, matching on the language part of the locale ("pl-PL" -> "pl")
+fn localized_text(locale: Option<&str>, id: &str) -> Option<&'static LocalizedText> {
+    let language = locale?
+        .split(['-', '_'])
+        .next()?
+        .to_ascii_lowercase();
+    LOCALIZED_TEXT
+        .iter()
+        .find(|text| text.locale == language && text.id == id)
+}
+
+/// Built-in catalog merged with the remote catalog (if one was loaded). Names/descriptions of
+/// built-in models are in `locale`, falling back to English.
+pub fn get_model_catalog(locale: Option<&str>) -> Vec<LocalModelCatalogEntry> {
+    let mut catalog = builtin_catalog(locale);
+
+    let guard = match REMOTE_CATALOG.read() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if let Some(remote) = guard.as_ref() {
+        for entry in &remote.entries {
+            match catalog.iter_mut().find(|existing| existing.id == entry.id) {
+                Some(existing) if remote.trusted => *existing = entry.clone(),
+                Some(_) => {}
+                None => catalog.push(entry.clone()),
+            }
+        }
+    }
+    drop(guard);
+
+    // User-added models never shadow catalog entries
+    let custom = match CUSTOM_MODELS.read() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    for entry in custom.iter() {
+        if !catalog.iter().any(|existing| existing.id == entry.id) {
+            catalog.push(entry.clone());
+        }
+    }
+
+    catalog
+}
+
+fn builtin_catalog(locale: Option<&str>) -> Vec<LocalModelCatalogEntry> {
     CATALOG_DEFS
         .iter()
-        .map(|def| LocalModelCatalogEntry {
-            id: def.id.to_string(),
-            name: def.name.to_string(),
-            category: def.category.clone(),
-            description: def.description.to_string(),
-            size_mb: def.size_mb,
-            download_url: def.download_url.to_string(),
-            filename: def.filename.to_string(),
-            speed_rating: def.speed_rating,
-            accuracy_rating: def.accuracy_rating,
-            language_support: def.language_support.to_string(),
+        .map(|def| {
+            let localized = localized_text(locale, def.id);
+            LocalModelCatalogEntry {
+                id: def.id.to_string(),
+                name: localized
+                    .and_then(|text| text.name)
+                    .unwrap_or(def.name)
+                    .to_string(),
+                category: def.category.clone(),
+                description: localized
+                    .map(|text| text.description)
+                    .unwrap_or(def.description)
+                    .to_string(),
+                size_mb: def.size_mb,
+                download_url: def.download_url.to_string(),
+                filename: def.filename.to_string(),
+                speed_rating: def.speed_rating,
+                accuracy_rating: def.accuracy_rating,
+                language_support: def.language_support.to_string(),
+                sha256: None,
+            }
         })
         .collect()
 }
 
 pub fn find_catalog_entry(model_id: &str) -> Option<LocalModelCatalogEntry> {
-    get_model_catalog().into_iter().find(|e| e.id == model_id)
+    get_model_catalog(None).into_iter().find(|e| e.id == model_id)
+}
+
+/// File in the models directory caching the last fetched remote catalog
+const REMOTE_CATALOG_FILE: &str = "remote-catalog.json";
+
+/// Entries from a remote catalog file, kept next to the models so they survive restarts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteCatalog {
+    pub url: String,
+    /// Trusted catalogs may replace built-in entries with the same id
+    pub trusted: bool,
+    pub entries: Vec<LocalModelCatalogEntry>,
+}
+
+static REMOTE_CATALOG: RwLock<Option<RemoteCatalog>> = RwLock::new(None);
+
+/// Remote catalog entry; optional presentation fields get neutral defaults
+#[derive(Deserialize)]
+struct RemoteCatalogEntry {
+    id: String,
+    name: String,
+    #[serde(default)]
+    description: String,
+    size_mb: u64,
+    download_url: String,
+    filename: String,
+    sha256: String,
+    #[serde(default = "default_rating")]
+    speed_rating: u8,
+    #[serde(default = "default_rating")]
+    accuracy_rating: u8,
+    #[serde(default = "default_language_support")]
+    language_support: String,
+}
+
+fn default_rating() -> u8 {
+    3
+}
+
+fn default_language_support() -> String {
+    "multilingual".to_string()
+}
+
+/// The remote file is either `{"models": [...]}` or a bare array of entries
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RemoteCatalogFile {
+    Wrapped { models: Vec<RemoteCatalogEntry> },
+    Bare(Vec<RemoteCatalogEntry>),
+}
+
+/// Parse and validate a remote catalog document. Every entry must carry a SHA-256 so the
+/// downloaded file can be verified, point at an https URL, and use a plain file name that
+/// doesn't collide with a different built-in model's file.
+pub fn parse_remote_catalog(json: &str) -> Result<Vec<LocalModelCatalogEntry>, String> {
+    let entries = match serde_json::from_str::<RemoteCatalogFile>(json)
+        .map_err(|e| format!("Invalid model catalog: {}", e))?
+    {
+        RemoteCatalogFile::Wrapped { models } => models,
+        RemoteCatalogFile::Bare(models) => models,
+    };
+
+    let builtin = builtin_catalog(None);
+    let mut parsed: Vec<LocalModelCatalogEntry> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let id = entry.id.trim().to_string();
+        if id.is_empty() || entry.name.trim().is_empty() {
+            return Err("Model catalog entries need an id and a name".to_string());
+        }
+        if parsed.iter().any(|other| other.id == id) {
+            return Err(format!("Duplicate model id in catalog: {}", id));
+        }
+        if !entry.download_url.starts_with("https://") {
+            return Err(format!("Model {}: download_url must be an https URL", id));
+        }
+        let plain_filename = Path::new(&entry.filename).file_name().and_then(|name| name.to_str());
+        if entry.filename.is_empty() || plain_filename != Some(entry.filename.as_str()) || entry.filename.starts_with('.') {
+            return Err(format!("Model {}: filename must be a plain file name", id));
+        }
+        if builtin.iter().any(|model| model.filename == entry.filename && model.id != id)
+            || parsed.iter().any(|model| model.filename == entry.filename)
+        {
+            return Err(format!("Model {}: filename {} is already used by another model", id, entry.filename));
+        }
+        let sha256 = normalize_sha256(&id, &entry.sha256)?;
+        if entry.size_mb == 0 {
+            return Err(format!("Model {}: size_mb must be greater than 0", id));
+        }
+
+        parsed.push(LocalModelCatalogEntry {
+            id,
+            name: entry.name,
+            category: LocalModelCategory::SpeechToText,
+            description: entry.description,
+            size_mb: entry.size_mb,
+            download_url: entry.download_url,
+            filename: entry.filename,
+            speed_rating: entry.speed_rating.clamp(1, 5),
+            accuracy_rating: entry.accuracy_rating.clamp(1, 5),
+            language_support: entry.language_support,
+            sha256: Some(sha256),
+        });
+    }
+
+    Ok(parsed)
+}
+
+/// Make `remote` part of the catalog and cache it in `models_dir`
+pub fn set_remote_catalog(remote: RemoteCatalog, models_dir: &Path) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(&remote)
+        .map_err(|e| format!("Failed to serialize model catalog: {}", e))?;
+    write_atomic(&models_dir.join(REMOTE_CATALOG_FILE), json.as_bytes())
+        .map_err(|e| format!("Failed to cache model catalog: {}", e))?;
+
+    match REMOTE_CATALOG.write() {
+        Ok(mut guard) => *guard = Some(remote),
+        Err(poisoned) => *poisoned.into_inner() = Some(remote),
+    }
+    Ok(())
+}
+
+/// Restore the cached remote catalog, if any
+pub fn load_cached_remote_catalog(models_dir: &Path) {
+    let Ok(json) = std::fs::read_to_string(models_dir.join(REMOTE_CATALOG_FILE)) else {
+        return;
+    };
+    match serde_json::from_str::<RemoteCatalog>(&json) {
+        Ok(remote) => match REMOTE_CATALOG.write() {
+            Ok(mut guard) => *guard = Some(remote),
+            Err(poisoned) => *poisoned.into_inner() = Some(remote),
+        },
+        Err(e) => eprintln!("[LocalModels] Ignoring unreadable cached model catalog: {}", e),
+    }
+}
+
+/// Write a temp file next to `path` and rename it over `path`, so a crash mid-write never
+/// leaves a truncated file behind
+fn write_atomic(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let temp_path = path.with_extension("tmp");
+    {
+        let mut file = std::fs::File::create(&temp_path)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+    }
+    std::fs::rename(&temp_path, path)
+}
+
+/// Lowercased hex SHA-256, or an error naming the model
+fn normalize_sha256(id: &str, sha256: &str) -> Result<String, String> {
+    let sha256 = sha256.trim().to_ascii_lowercase();
+    if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Model {}: sha256 must be 64 hex characters", id));
+    }
+    Ok(sha256)
+}
+
+/// File in the models directory listing user-added models
+const CUSTOM_MODELS_FILE: &str = "custom-models.json";
+
+static CUSTOM_MODELS: RwLock<Vec<LocalModelCatalogEntry>> = RwLock::new(Vec::new());
+
+/// Add a user-provided GGML model (e.g. a fine-tuned whisper) of `size_mb` to the catalog and
+/// persist it. The id may not collide with any catalog model; the file is stored as `custom-{id}.bin`.
+pub fn add_custom_model(
+    id: &str,
+    name: &str,
+    download_url: &str,
+    sha256: &str,
+    size_mb: u64,
+    models_dir: &Path,
+) -> Result<LocalModelCatalogEntry, String> {
+    let id = id.trim();
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.') {
+        return Err("Model id may only contain letters, digits, '-', '_' and '.'".to_string());
+    }
+    if name.trim().is_empty() {
+        return Err("Model name is required".to_string());
+    }
+    if !download_url.starts_with("https://") && !download_url.starts_with("http://") {
+        return Err(format!("Model {}: download_url must be an http(s) URL", id));
+    }
+    let sha256 = normalize_sha256(id, sha256)?;
+    if size_mb == 0 {
+        return Err(format!("Model {}: size_mb must be greater than 0", id));
+    }
+    if get_model_catalog(None).iter().any(|entry| entry.id == id) {
+        return Err(format!("A model with id {} already exists", id));
+    }
+
+    let entry = LocalModelCatalogEntry {
+        id: id.to_string(),
+        name: name.trim().to_string(),
+        category: LocalModelCategory::SpeechToText,
+        description: String::new(),
+        size_mb,
+        download_url: download_url.to_string(),
+        filename: format!("custom-{}.bin", id),
+        speed_rating: default_rating(),
+        accuracy_rating: default_rating(),
+        language_support: default_language_support(),
+        sha256: Some(sha256),
+    };
+
+    let mut custom = match CUSTOM_MODELS.write() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let mut updated = custom.clone();
+    updated.push(entry.clone());
+    let json = serde_json::to_string_pretty(&updated)
+        .map_err(|e| format!("Failed to serialize custom models: {}", e))?;
+    write_atomic(&models_dir.join(CUSTOM_MODELS_FILE), json.as_bytes())
+        .map_err(|e| format!("Failed to save custom models: {}", e))?;
+    *custom = updated;
+
+    Ok(entry)
+}
+
+/// Restore user-added models, if any
+pub fn load_custom_models(models_dir: &Path) {
+    let Ok(json) = std::fs::read_to_string(models_dir.join(CUSTOM_MODELS_FILE)) else {
+        return;
+    };
+    match serde_json::from_str::<Vec<LocalModelCatalogEntry>>(&json) {
+        Ok(entries) => match CUSTOM_MODELS.write() {
+            Ok(mut guard) => *guard = entries,
+            Err(poisoned) => *poisoned.into_inner() = entries,
+        },
+        Err(e) => eprintln!("[LocalModels] Ignoring unreadable custom model list: {}", e),
+    }
 }
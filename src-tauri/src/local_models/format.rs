@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use crate::local_models::whisper::{segments_to_text, TranscriptSegment};
+
+/// Output format for local transcription, mirroring the remote `response_format` values
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocalResponseFormat {
+    #[default]
+    Text,
+    Json,
+    VerboseJson,
+    Srt,
+    Vtt,
+}
+
+/// Format a subtitle timestamp as `HH:MM:SS{sep}mmm`
+fn subtitle_time(ms: u64, separator: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        (ms / 60_000) % 60,
+        (ms / 1000) % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+/// Render segments in the requested format. JSON shapes follow OpenAI's transcription
+/// responses (times in seconds) so local and remote output can be handled the same way.
+pub fn format_transcript(
+    segments: &[TranscriptSegment],
+    format: LocalResponseFormat,
+    language: Option<&str>,
+    duration_ms: u64,
+) -> String {
+    let text = segments_to_text(segments);
+    let cues = segments.iter().filter(|segment| !segment.text.is_empty());
+
+    match format {
+        LocalResponseFormat::Text => text,
+        LocalResponseFormat::Json => serde_json::json!({ "text": text }).to_string(),
+        LocalResponseFormat::VerboseJson => {
+            let segments: Vec<serde_json::Value> = segments
+                .iter()
+                .enumerate()
+                .map(|(id, segment)| {
+                    serde_json::json!({
+                        "id": id,
+                        "start": segment.start_ms as f64 / 1000.0,
+                        "end": segment.end_ms as f64 / 1000.0,
+                        "text": segment.text,
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "task": "transcribe",
+                "language": language,
+                "duration": duration_ms as f64 / 1000.0,
+                "text": text,
+                "segments": segments,
+            })
+            .to_string()
+        }
+        LocalResponseFormat::Srt => cues
+            .enumerate()
+            .map(|(index, segment)| {
+                format!(
+                    "{}\n{} --> {}\n{}\n",
+                    index + 1,
+                    subtitle_time(segment.start_ms, ','),
+                    subtitle_time(segment.end_ms, ','),
+                    segment.text
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        LocalResponseFormat::Vtt => {
+            let mut vtt = String::from("WEBVTT\n");
+            for segment in cues {
+                vtt.push_str(&format!(
+                    "\n{} --> {}\n{}\n",
+                    subtitle_time(segment.start_ms, '.'),
+                    subtitle_time(segment.end_ms, '.'),
+                    segment.text
+                ));
+            }
+            vtt
+        }
+    }
+}
@@ -1,8 +1,14 @@
+use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use serde::{Deserialize, Serialize};
-use crate::local_models::catalog::{find_catalog_entry, get_model_catalog, LocalModelCatalogEntry, LocalModelCategory};
+use sha2::{Digest, Sha256};
+use crate::local_models::catalog::{
+    find_catalog_entry, get_model_catalog, load_cached_remote_catalog, load_custom_models, parse_remote_catalog,
+    set_remote_catalog, LocalModelCatalogEntry, LocalModelCategory, RemoteCatalog,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocalModelStatus {
@@ -14,11 +20,70 @@ pub struct LocalModelStatus {
     pub downloaded: bool,
     pub downloading: bool,
     pub download_progress: f64,
+    /// Percent already on disk from an interrupted download (the `.downloading` temp file)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partial_progress: Option<f64>,
     pub speed_rating: u8,
     pub accuracy_rating: u8,
     pub language_support: String,
 }
 
+/// Window over which download speed is measured
+const SPEED_WINDOW: Duration = Duration::from_secs(5);
+/// Weight of the newest window measurement in the smoothed speed
+const SPEED_SMOOTHING: f64 = 0.3;
+
+/// Payload of `local-model-download-progress-{model_id}` events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadProgress {
+    pub downloaded_bytes: u64,
+    /// 0 when the server did not send a Content-Length
+    pub total_bytes: u64,
+    pub percent: f64,
+    pub bytes_per_second: f64,
+    /// None until a speed is known or when the total size is unknown
+    pub eta_seconds: Option<f64>,
+}
+
+/// Smoothed download speed: a rate measured over a sliding window, blended with
+/// the previous estimate so it doesn't jump between chunks
+struct SpeedTracker {
+    samples: VecDeque<(Instant, u64)>,
+    smoothed: Option<f64>,
+}
+
+impl SpeedTracker {
+    fn new() -> Self {
+        Self {
+            samples: VecDeque::from([(Instant::now(), 0)]),
+            smoothed: None,
+        }
+    }
+
+    fn update(&mut self, downloaded: u64) -> f64 {
+        let now = Instant::now();
+        self.samples.push_back((now, downloaded));
+        // Keep one sample older than the window as the measurement baseline
+        while self.samples.len() > 2 && now.duration_since(self.samples[1].0) > SPEED_WINDOW {
+            self.samples.pop_front();
+        }
+
+        let (start_time, start_bytes) = self.samples[0];
+        let elapsed = now.duration_since(start_time).as_secs_f64();
+        if elapsed <= 0.0 {
+            return self.smoothed.unwrap_or(0.0);
+        }
+
+        let rate = (downloaded - start_bytes) as f64 / elapsed;
+        let smoothed = match self.smoothed {
+            Some(previous) => previous + SPEED_SMOOTHING * (rate - previous),
+            None => rate,
+        };
+        self.smoothed = Some(smoothed);
+        smoothed
+    }
+}
+
 pub struct LocalModelManager {
     models_dir: PathBuf,
     downloading: Arc<RwLock<std::collections::HashSet<String>>>,
@@ -30,6 +95,8 @@ impl LocalModelManager {
         if !models_dir.exists() {
             let _ = std::fs::create_dir_all(&models_dir);
         }
+        load_cached_remote_catalog(&models_dir);
+        load_custom_models(&models_dir);
         Self {
             models_dir,
             downloading: Arc::new(RwLock::new(std::collections::HashSet::new())),
@@ -40,13 +107,34 @@ impl LocalModelManager {
         self.models_dir.join(&entry.filename)
     }
 
-    pub async fn list_models(&self) -> Vec<LocalModelStatus> {
+    /// Temp file a download writes to before it is renamed into place
+    fn temp_path(&self, entry: &LocalModelCatalogEntry) -> PathBuf {
+        self.model_path(entry).with_extension("downloading")
+    }
+
+    /// Progress of a leftover partial download, estimated against the catalog size
+    fn partial_progress(&self, entry: &LocalModelCatalogEntry) -> Option<f64> {
+        let partial_bytes = std::fs::metadata(self.temp_path(entry)).ok()?.len();
+        let expected_bytes = entry.size_mb * 1024 * 1024;
+        if partial_bytes == 0 || expected_bytes == 0 {
+            return None;
+        }
+        // Catalog sizes are approximate, so never report a partial file as complete
+        Some((partial_bytes as f64 / expected_bytes as f64 * 100.0).min(99.0))
+    }
+
+    pub async fn list_models(&self, locale: Option<&str>) -> Vec<LocalModelStatus> {
         let downloading = self.downloading.read().await;
-        get_model_catalog()
+        get_model_catalog(locale)
             .into_iter()
             .map(|entry| {
                 let downloaded = self.model_path(&entry).exists();
                 let is_downloading = downloading.contains(&entry.id);
+                let partial_progress = if downloaded || is_downloading {
+                    None
+                } else {
+                    self.partial_progress(&entry)
+                };
                 LocalModelStatus {
                     id: entry.id,
                     name: entry.name,
@@ -56,6 +144,7 @@ impl LocalModelManager {
                     downloaded,
                     downloading: is_downloading,
                     download_progress: if is_downloading { 0.0 } else if downloaded { 100.0 } else { 0.0 },
+                    partial_progress,
                     speed_rating: entry.speed_rating,
                     accuracy_rating: entry.accuracy_rating,
                     language_support: entry.language_support,
@@ -67,7 +156,7 @@ impl LocalModelManager {
     pub async fn download_model(
         &self,
         model_id: String,
-        progress_callback: impl Fn(f64) + Send + 'static,
+        progress_callback: impl Fn(DownloadProgress) + Send + 'static,
     ) -> Result<(), String> {
         let entry = find_catalog_entry(&model_id)
             .ok_or_else(|| format!("Model not found in catalog: {}", model_id))?;
@@ -86,7 +175,7 @@ impl LocalModelManager {
         let model_id_clone = model_id.clone();
 
         // Download in a separate task
-        let result = Self::download_file(&entry.download_url, &dest_path, progress_callback).await;
+        let result = Self::download_file(&entry.download_url, &dest_path, entry.sha256.as_deref(), progress_callback).await;
 
         // Remove from downloading set
         {
@@ -100,9 +189,10 @@ impl LocalModelManager {
     async fn download_file(
         url: &str,
         dest: &PathBuf,
-        progress_callback: impl Fn(f64) + Send + 'static,
+        expected_sha256: Option<&str>,
+        progress_callback: impl Fn(DownloadProgress) + Send + 'static,
     ) -> Result<(), String> {
-        let client = reqwest::Client::builder()
+        let client = crate::http_client::client_builder()
             .timeout(std::time::Duration::from_secs(3600))
             .build()
             .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
@@ -119,6 +209,7 @@ impl LocalModelManager {
 
         let total_size = response.content_length().unwrap_or(0);
         let mut downloaded: u64 = 0;
+        let mut speed = SpeedTracker::new();
 
         // Write to a temp file first, then rename
         let temp_path = dest.with_extension("downloading");
@@ -128,6 +219,7 @@ impl LocalModelManager {
             .map_err(|e| format!("Failed to create file: {}", e))?;
 
         let mut stream = response.bytes_stream();
+        let mut hasher = Sha256::new();
         use futures::StreamExt;
         use tokio::io::AsyncWriteExt;
 
@@ -136,26 +228,122 @@ impl LocalModelManager {
             file.write_all(&chunk)
                 .await
                 .map_err(|e| format!("Failed to write: {}", e))?;
+            hasher.update(&chunk);
 
             downloaded += chunk.len() as u64;
             if total_size > 0 {
-                let progress = (downloaded as f64 / total_size as f64) * 100.0;
-                progress_callback(progress);
+                let bytes_per_second = speed.update(downloaded);
+                let eta_seconds = (bytes_per_second > 0.0)
+                    .then(|| total_size.saturating_sub(downloaded) as f64 / bytes_per_second);
+                progress_callback(DownloadProgress {
+                    downloaded_bytes: downloaded,
+                    total_bytes: total_size,
+                    percent: (downloaded as f64 / total_size as f64) * 100.0,
+                    bytes_per_second,
+                    eta_seconds,
+                });
             }
         }
 
         file.flush().await.map_err(|e| format!("Failed to flush: {}", e))?;
         drop(file);
 
+        if let Some(expected) = expected_sha256 {
+            let actual = hex_digest(&hasher.finalize());
+            if !actual.eq_ignore_ascii_case(expected) {
+                let _ = tokio::fs::remove_file(&temp_path).await;
+                return Err(format!("Checksum mismatch: expected sha256 {}, got {}", expected, actual));
+            }
+        }
+
         // Rename temp file to final destination
         tokio::fs::rename(&temp_path, dest)
             .await
             .map_err(|e| format!("Failed to finalize download: {}", e))?;
 
-        progress_callback(100.0);
+        progress_callback(DownloadProgress {
+            downloaded_bytes: downloaded,
+            total_bytes: total_size.max(downloaded),
+            percent: 100.0,
+            bytes_per_second: speed.smoothed.unwrap_or(0.0),
+            eta_seconds: Some(0.0),
+        });
         Ok(())
     }
 
+    /// Fetch a remote model catalog, validate it and merge it into the catalog.
+    /// The result is cached in the models directory and restored on startup.
+    pub async fn refresh_catalog(&self, url: &str, trusted: bool) -> Result<(), String> {
+        if !url.starts_with("https://") {
+            return Err("Model catalog URL must use https".to_string());
+        }
+
+        let client = crate::http_client::client_builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let response = client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch model catalog: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Model catalog request failed with status: {}", response.status()));
+        }
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read model catalog: {}", e))?;
+
+        let entries = parse_remote_catalog(&body)?;
+        set_remote_catalog(
+            RemoteCatalog {
+                url: url.to_string(),
+                trusted,
+                entries,
+            },
+            &self.models_dir,
+        )
+    }
+
+    /// Add a user-provided model to the catalog (see `catalog::add_custom_model`), sized from
+    /// the Content-Length of a HEAD request to `download_url`
+    pub async fn add_custom_model(
+        &self,
+        id: &str,
+        name: &str,
+        download_url: &str,
+        sha256: &str,
+    ) -> Result<LocalModelCatalogEntry, String> {
+        let size_mb = Self::remote_size_mb(download_url).await?;
+        crate::local_models::catalog::add_custom_model(id, name, download_url, sha256, size_mb, &self.models_dir)
+    }
+
+    /// Size of the file at `url` in MB (rounded up), from a HEAD request
+    async fn remote_size_mb(url: &str) -> Result<u64, String> {
+        let client = crate::http_client::client_builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let response = client
+            .head(url)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach model download URL: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Model download URL returned status: {}", response.status()));
+        }
+        // Read the header itself: the (empty) HEAD body would report a length of 0
+        let bytes = response
+            .headers()
+            .get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+            .filter(|bytes| *bytes > 0)
+            .ok_or_else(|| "Model download URL did not report the file size".to_string())?;
+        Ok(bytes.div_ceil(1024 * 1024))
+    }
+
     pub async fn delete_model(&self, model_id: &str) -> Result<(), String> {
         let entry = find_catalog_entry(model_id)
             .ok_or_else(|| format!("Model not found in catalog: {}", model_id))?;
@@ -180,3 +368,7 @@ impl LocalModelManager {
         }
     }
 }
+
+fn hex_digest(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -1,6 +1,10 @@
+pub mod cache;
 pub mod catalog;
+pub mod format;
 pub mod manager;
 pub mod whisper;
 
+pub use catalog::LocalModelCatalogEntry;
+pub use format::LocalResponseFormat;
 pub use manager::{LocalModelManager, LocalModelStatus};
-pub use whisper::LocalWhisperEngine;
+pub use whisper::{LanguageCandidate, LiveTranscript, LocalModelBenchmarkResult, LocalTranscriptionError, LocalWhisperEngine, SegmentedTranscript, WhisperChunking, WhisperOptions, WhisperSampling, WhisperThresholds};
@@ -1,5 +1,366 @@
 use std::path::PathBuf;
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use std::sync::Arc;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState};
+use crate::audio::recorder::LiveAudioTap;
+use crate::local_models::cache;
+
+/// Benchmark result for a single local model run against a sample clip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalModelBenchmarkResult {
+    pub model_id: String,
+    /// Real-time factor: inference time divided by audio duration (lower is faster)
+    pub rtf: f64,
+    /// Wall-clock time including model load and inference
+    pub inference_ms: u64,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A transcribed span of audio with its timing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+    /// Per-word timing, only when word timestamps were requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<TranscriptWord>>,
+}
+
+/// A single word with its timing, assembled from whisper tokens
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptWord {
+    pub word: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// Local transcription with segment (and optionally word) timings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentedTranscript {
+    pub text: String,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+/// Interim result of a live transcription
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveTranscript {
+    /// Text that will not change anymore
+    pub stable_text: String,
+    /// Stable text followed by the still-changing tail of the recording
+    pub text: String,
+    /// Set on the last event, sent after the recording ended
+    pub is_final: bool,
+}
+
+/// Hardware a whisper context runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WhisperBackend {
+    Cpu,
+    Metal,
+    Cuda,
+}
+
+impl WhisperBackend {
+    /// GPU backend compiled into this build: Metal on macOS, CUDA with the `cuda` feature
+    pub fn gpu() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            Some(Self::Metal)
+        } else if cfg!(feature = "cuda") {
+            Some(Self::Cuda)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cpu => "cpu",
+            Self::Metal => "metal",
+            Self::Cuda => "cuda",
+        }
+    }
+}
+
+/// Segments of a transcription and the language whisper decoded them in
+#[derive(Debug, Clone)]
+pub struct DecodedTranscript {
+    pub segments: Vec<TranscriptSegment>,
+    /// ISO 639-1 code, e.g. "pl"; the requested language, or the detected one with auto-detection
+    pub language: Option<String>,
+    /// Backend the model actually ran on (CPU when GPU initialization failed)
+    pub backend: WhisperBackend,
+}
+
+/// Why a local transcription failed, tagged by `kind` so the UI can react to it
+/// (e.g. offer to download a missing model)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LocalTranscriptionError {
+    /// The model isn't on disk; `size_mb` is its catalog size (None for unknown models)
+    ModelNotDownloaded { model_id: String, size_mb: Option<u64> },
+    /// The model file exists but whisper can't load it
+    ModelCorrupt { message: String },
+    /// The audio couldn't be decoded
+    DecodeFailed { message: String },
+    InferenceFailed { message: String },
+    /// Invalid options, timeout, cancellation, or a failed cloud request in commands that
+    /// also transcribe in the cloud
+    Failed { message: String },
+}
+
+impl std::fmt::Display for LocalTranscriptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ModelNotDownloaded { model_id, .. } => write!(f, "Model {} is not downloaded", model_id),
+            Self::ModelCorrupt { message } => write!(f, "Model file is corrupt: {}", message),
+            Self::DecodeFailed { message } | Self::InferenceFailed { message } | Self::Failed { message } => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl From<String> for LocalTranscriptionError {
+    fn from(message: String) -> Self {
+        Self::Failed { message }
+    }
+}
+
+impl From<LocalTranscriptionError> for String {
+    fn from(error: LocalTranscriptionError) -> Self {
+        error.to_string()
+    }
+}
+
+fn inference_failed(message: String) -> LocalTranscriptionError {
+    LocalTranscriptionError::InferenceFailed { message }
+}
+
+/// Language of the last decode, as an ISO 639-1 code
+fn decoded_language(state: &WhisperState) -> Option<String> {
+    let id = state.full_lang_id_from_state().ok()?;
+    whisper_rs::get_lang_str(id).map(str::to_string)
+}
+
+/// Normalize a language reported by a provider (a code like "pl" or a whisper language name
+/// like "polish", as returned in OpenAI's `verbose_json`) to an ISO 639-1 code
+pub fn language_code(language: &str) -> Option<String> {
+    let language = language.trim().to_ascii_lowercase();
+    (0..=whisper_rs::get_lang_max_id())
+        .find(|&id| {
+            whisper_rs::get_lang_str(id) == Some(language.as_str())
+                || whisper_rs::get_lang_str_full(id) == Some(language.as_str())
+        })
+        .and_then(whisper_rs::get_lang_str)
+        .map(str::to_string)
+}
+
+/// Join segment texts into a single transcript
+pub fn segments_to_text(segments: &[TranscriptSegment]) -> String {
+    segments
+        .iter()
+        .map(|segment| segment.text.as_str())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A candidate spoken language with its detection probability
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageCandidate {
+    /// ISO 639-1 code, e.g. "en", "pl"
+    pub language: String,
+    pub probability: f32,
+}
+
+/// Decoding strategy for local whisper.
+/// Greedy is fastest; beam search is noticeably more accurate on hard audio but roughly
+/// `beam_size` times slower, so it's best reserved for capable hardware or offline jobs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WhisperSampling {
+    Greedy { best_of: i32 },
+    BeamSearch { beam_size: i32 },
+}
+
+impl Default for WhisperSampling {
+    fn default() -> Self {
+        Self::Greedy { best_of: 1 }
+    }
+}
+
+impl WhisperSampling {
+    fn to_strategy(&self) -> SamplingStrategy {
+        match self {
+            Self::Greedy { best_of } => SamplingStrategy::Greedy {
+                best_of: (*best_of).clamp(1, 8),
+            },
+            Self::BeamSearch { beam_size } => SamplingStrategy::BeamSearch {
+                beam_size: (*beam_size).clamp(1, 8),
+                // Not used by whisper.cpp yet
+                patience: -1.0,
+            },
+        }
+    }
+}
+
+/// Thresholds that suppress hallucinated text on silent or very quiet audio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WhisperThresholds {
+    /// Segments whose no-speech probability exceeds this are treated as silence
+    pub no_speech_thold: f32,
+    /// Decodes with average log-probability below this are retried at a higher temperature
+    pub logprob_thold: f32,
+    /// Temperature step for fallback decoding (0 disables fallback)
+    pub temperature_inc: f32,
+}
+
+impl Default for WhisperThresholds {
+    fn default() -> Self {
+        // Slightly stricter than whisper.cpp's no_speech default so quiet pauses
+        // don't turn into phantom phrases, while genuine quiet speech still decodes
+        Self {
+            no_speech_thold: 0.5,
+            logprob_thold: -1.0,
+            temperature_inc: 0.2,
+        }
+    }
+}
+
+/// Tuning options for a local transcription run
+#[derive(Debug, Clone, Default)]
+pub struct WhisperOptions {
+    pub sampling: WhisperSampling,
+    pub thresholds: WhisperThresholds,
+    /// Translate speech to English instead of transcribing it in the spoken language.
+    /// Whisper can only translate *into* English, and English-only models can't translate.
+    pub translate: bool,
+    /// Inference threads; None uses up to 4 so transcription doesn't hog the machine
+    pub threads: Option<i32>,
+    /// Run on the GPU when this build has a GPU backend, falling back to the CPU otherwise
+    pub gpu: bool,
+}
+
+impl WhisperOptions {
+    /// Check `threads` against the machine's available parallelism
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(threads) = self.threads {
+            let available = std::thread::available_parallelism()
+                .map(|n| n.get() as i32)
+                .unwrap_or(1);
+            if threads < 1 || threads > available {
+                return Err(format!(
+                    "threads must be between 1 and {} (available parallelism), got {}",
+                    available, threads
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn n_threads(&self) -> i32 {
+        self.threads.unwrap_or_else(num_cpus)
+    }
+}
+
+/// Chunking for long local transcriptions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WhisperChunking {
+    /// Length of each chunk fed to whisper
+    pub chunk_length_ms: u64,
+    /// Audio shared by neighbouring chunks; must be less than half the chunk length
+    pub overlap_ms: u64,
+}
+
+impl Default for WhisperChunking {
+    fn default() -> Self {
+        Self {
+            chunk_length_ms: 30_000,
+            overlap_ms: 2_000,
+        }
+    }
+}
+
+impl WhisperChunking {
+    fn validate(&self) -> Result<(), String> {
+        if self.chunk_length_ms < 5_000 {
+            return Err("Chunk length must be at least 5000 ms".to_string());
+        }
+        if self.overlap_ms * 2 >= self.chunk_length_ms {
+            return Err("Overlap must be less than half the chunk length".to_string());
+        }
+        Ok(())
+    }
+
+    /// Split `total_samples` 16 kHz samples into overlapping chunks. Each chunk keeps the tokens
+    /// whose midpoint falls between the middles of its overlaps with its neighbours, so every
+    /// moment of the clip is kept by exactly one chunk.
+    fn windows(&self, total_samples: usize) -> Vec<ChunkWindow> {
+        let samples_per_ms = WHISPER_SAMPLE_RATE as u64 / 1000;
+        let chunk_len = (self.chunk_length_ms * samples_per_ms) as usize;
+        let step = ((self.chunk_length_ms - self.overlap_ms) * samples_per_ms) as usize;
+        let half_overlap_ms = self.overlap_ms as i64 / 2;
+
+        let mut windows = Vec::new();
+        let mut start = 0usize;
+        loop {
+            let end = (start + chunk_len).min(total_samples);
+            let is_first = start == 0;
+            let is_last = end == total_samples;
+            let chunk_ms = ((end - start) as u64 / samples_per_ms) as i64;
+            windows.push(ChunkWindow {
+                start,
+                end,
+                offset_ms: (start as u64 / samples_per_ms) as i64,
+                keep_from_ms: if is_first { i64::MIN } else { half_overlap_ms },
+                keep_until_ms: if is_last { i64::MAX } else { chunk_ms - half_overlap_ms },
+            });
+            if is_last {
+                return windows;
+            }
+            start += step;
+        }
+    }
+}
+
+/// One chunk of a chunked transcription
+#[derive(Debug)]
+struct ChunkWindow {
+    /// Sample range fed to whisper
+    start: usize,
+    end: usize,
+    /// Start of the chunk in the clip
+    offset_ms: i64,
+    /// Tokens are kept when their midpoint, relative to the chunk start, is in [keep_from_ms, keep_until_ms)
+    keep_from_ms: i64,
+    keep_until_ms: i64,
+}
+
+impl ChunkWindow {
+    fn keeps(&self, mid_ms: i64) -> bool {
+        mid_ms >= self.keep_from_ms && mid_ms < self.keep_until_ms
+    }
+}
+
+const WHISPER_SAMPLE_RATE: u32 = 16000;
+/// How often live transcription re-decodes the recording
+const LIVE_INTERVAL: Duration = Duration::from_secs(2);
+/// Whisper rejects inputs shorter than one second
+const LIVE_MIN_WINDOW_MS: u64 = 1_000;
+/// Window length after which words are committed and the window slides forward
+const LIVE_COMMIT_AFTER_MS: u64 = 10_000;
+/// Audio at the end of the window that is never committed; words in it may be cut
+/// mid-way and are decoded again with more context on the next pass
+const LIVE_TAIL_MS: u64 = 2_000;
+/// Whisper's language detection only looks at the first 30 s window
+const LANGUAGE_DETECTION_MS: usize = 30_000;
 
 pub struct LocalWhisperEngine;
 
@@ -8,85 +369,487 @@ impl LocalWhisperEngine {
         model_path: &PathBuf,
         audio_data: &[u8],
         language: Option<&str>,
+        options: &WhisperOptions,
     ) -> Result<String, String> {
-        // Parse WAV audio data
-        let samples = Self::wav_to_f32_samples(audio_data)?;
+        let transcript = Self::transcribe_segments(model_path, audio_data, language, options)?;
+        Ok(segments_to_text(&transcript.segments))
+    }
 
-        // Resample to 16kHz mono if needed (whisper requires 16kHz)
-        let samples_16k = Self::ensure_16khz(&samples, audio_data)?;
+    /// Transcribe and keep whisper's segment boundaries and timings
+    pub fn transcribe_segments(
+        model_path: &PathBuf,
+        audio_data: &[u8],
+        language: Option<&str>,
+        options: &WhisperOptions,
+    ) -> Result<DecodedTranscript, LocalTranscriptionError> {
+        Self::decode_segments(model_path, audio_data, language, options, false)
+    }
 
-        // Create whisper context from model file
-        let ctx = WhisperContext::new_with_params(
-            model_path.to_str().ok_or("Invalid model path")?,
-            WhisperContextParameters::default(),
-        )
-        .map_err(|e| format!("Failed to load whisper model: {}", e))?;
+    /// Transcribe with segment timings and, when `word_timestamps` is set, per-word timings
+    /// from whisper's token timestamps (slightly slower)
+    pub fn transcribe_with_segments(
+        model_path: &PathBuf,
+        audio_data: &[u8],
+        language: Option<&str>,
+        options: &WhisperOptions,
+        word_timestamps: bool,
+    ) -> Result<SegmentedTranscript, String> {
+        let segments = Self::decode_segments(model_path, audio_data, language, options, word_timestamps)?.segments;
+        Ok(SegmentedTranscript {
+            text: segments_to_text(&segments),
+            segments,
+        })
+    }
 
-        let mut state = ctx.create_state()
-            .map_err(|e| format!("Failed to create whisper state: {}", e))?;
+    fn decode_segments(
+        model_path: &PathBuf,
+        audio_data: &[u8],
+        language: Option<&str>,
+        options: &WhisperOptions,
+        word_timestamps: bool,
+    ) -> Result<DecodedTranscript, LocalTranscriptionError> {
+        let samples_16k = Self::prepare_samples(audio_data)?;
 
-        // Configure transcription parameters
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        // Create whisper context from model file
+        let (ctx, backend) = Self::load_context(model_path, options.gpu)?;
+        Self::check_translate(&ctx, options)?;
 
-        // Set language if provided
-        if let Some(lang) = language {
-            let lang_code = lang.split('-').next().unwrap_or(lang);
-            params.set_language(Some(lang_code));
-        } else {
-            params.set_language(Some("auto"));
-        }
+        let mut state = ctx.create_state()
+            .map_err(|e| inference_failed(format!("Failed to create whisper state: {}", e)))?;
 
-        params.set_print_special(false);
-        params.set_print_progress(false);
-        params.set_print_realtime(false);
-        params.set_print_timestamps(false);
-        params.set_suppress_blank(true);
-        params.set_n_threads(num_cpus());
+        let mut params = Self::full_params(language, options);
+        params.set_token_timestamps(word_timestamps);
+        let token_eot = ctx.token_eot();
 
         // Run inference
         state.full(params, &samples_16k)
-            .map_err(|e| format!("Whisper inference failed: {}", e))?;
+            .map_err(|e| inference_failed(format!("Whisper inference failed: {}", e)))?;
 
         // Collect transcription segments
         let num_segments = state.full_n_segments()
-            .map_err(|e| format!("Failed to get segments: {}", e))?;
+            .map_err(|e| inference_failed(format!("Failed to get segments: {}", e)))?;
 
-        let mut text = String::new();
+        let mut segments = Vec::with_capacity(num_segments.max(0) as usize);
         for i in 0..num_segments {
-            if let Ok(segment) = state.full_get_segment_text(i) {
-                text.push_str(&segment);
+            let Ok(text) = state.full_get_segment_text(i) else {
+                continue;
+            };
+            // Segment timestamps are in centiseconds
+            let start_ms = state.full_get_segment_t0(i).unwrap_or(0).max(0) as u64 * 10;
+            let end_ms = state.full_get_segment_t1(i).unwrap_or(0).max(0) as u64 * 10;
+            let words = if word_timestamps {
+                Some(Self::segment_words(&state, i, token_eot).map_err(inference_failed)?)
+            } else {
+                None
+            };
+            segments.push(TranscriptSegment {
+                start_ms,
+                end_ms,
+                text: text.trim().to_string(),
+                words,
+            });
+        }
+
+        Ok(DecodedTranscript {
+            segments,
+            language: decoded_language(&state),
+            backend,
+        })
+    }
+
+    /// Transcribe long audio in overlapping chunks and stitch the results.
+    /// Each chunk is decoded with token timestamps; tokens are kept only on their side of the
+    /// midpoint of each overlap region, so words decoded twice at a boundary appear once and
+    /// words cut by one chunk's edge are taken from the neighbouring chunk.
+    /// Segment timings are rebuilt from the kept tokens, relative to the whole clip.
+    pub fn transcribe_chunked(
+        model_path: &PathBuf,
+        audio_data: &[u8],
+        language: Option<&str>,
+        options: &WhisperOptions,
+        chunking: &WhisperChunking,
+    ) -> Result<DecodedTranscript, LocalTranscriptionError> {
+        chunking.validate()?;
+
+        let samples_16k = Self::prepare_samples(audio_data)?;
+        let (ctx, backend) = Self::load_context(model_path, options.gpu)?;
+        Self::check_translate(&ctx, options)?;
+        let mut state = ctx.create_state()
+            .map_err(|e| inference_failed(format!("Failed to create whisper state: {}", e)))?;
+
+        let token_eot = ctx.token_eot();
+
+        let mut segments = Vec::new();
+        // Taken from the first chunk, as whisper does for a single pass
+        let mut language = None;
+
+        for window in chunking.windows(samples_16k.len()) {
+            let is_first = window.start == 0;
+            let offset_ms = window.offset_ms;
+
+            let mut params = Self::full_params(language, options);
+            params.set_token_timestamps(true);
+            state.full(params, &samples_16k[window.start..window.end])
+                .map_err(|e| inference_failed(format!("Whisper inference failed at {} ms: {}", offset_ms, e)))?;
+            if is_first {
+                language = decoded_language(&state);
+            }
+
+            let num_segments = state.full_n_segments()
+                .map_err(|e| inference_failed(format!("Failed to get segments: {}", e)))?;
+            for segment in 0..num_segments {
+                let num_tokens = state.full_n_tokens(segment)
+                    .map_err(|e| inference_failed(format!("Failed to get tokens: {}", e)))?;
+
+                let mut text = String::new();
+                let mut span: Option<(i64, i64)> = None;
+                for token in 0..num_tokens {
+                    let data = match state.full_get_token_data(segment, token) {
+                        Ok(data) => data,
+                        Err(_) => continue,
+                    };
+                    // Skip special tokens (timestamps, language, end-of-text)
+                    if data.id >= token_eot {
+                        continue;
+                    }
+                    // Token timestamps are in centiseconds; use the token midpoint
+                    let mid_ms = (data.t0 + data.t1) * 5;
+                    if !window.keeps(mid_ms) {
+                        continue;
+                    }
+                    if let Ok(piece) = state.full_get_token_text(segment, token) {
+                        text.push_str(&piece);
+                        let (t0, t1) = (data.t0 * 10, data.t1 * 10);
+                        span = Some(match span {
+                            Some((start, end)) => (start.min(t0), end.max(t1)),
+                            None => (t0, t1),
+                        });
+                    }
+                }
+
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                if let (false, Some((start, end))) = (text.is_empty(), span) {
+                    segments.push(TranscriptSegment {
+                        start_ms: (offset_ms + start).max(0) as u64,
+                        end_ms: (offset_ms + end).max(0) as u64,
+                        text,
+                        words: None,
+                    });
+                }
             }
         }
 
-        Ok(text.trim().to_string())
+        Ok(DecodedTranscript { segments, language, backend })
+    }
+
+    /// Transcribe an active recording every `LIVE_INTERVAL`, calling `emit` with interim text.
+    /// Each pass decodes the uncommitted window (from the last commit to now). Once the window
+    /// is longer than `LIVE_COMMIT_AFTER_MS`, words that ended before its last `LIVE_TAIL_MS`
+    /// are committed and the window slides to the end of the last committed word, so words cut
+    /// by the window edge are always re-decoded in full. Runs until the recording stops, then
+    /// emits a final result and returns the full transcript.
+    pub fn transcribe_live(
+        model_path: &PathBuf,
+        tap: &LiveAudioTap,
+        language: Option<&str>,
+        options: &WhisperOptions,
+        mut emit: impl FnMut(&LiveTranscript),
+    ) -> Result<String, String> {
+        // One context and state are reused for every pass
+        let (ctx, _) = Self::load_context(model_path, options.gpu)?;
+        Self::check_translate(&ctx, options)?;
+        let mut state = ctx.create_state()
+            .map_err(|e| format!("Failed to create whisper state: {}", e))?;
+        let token_eot = ctx.token_eot();
+
+        let mut stable_words: Vec<String> = Vec::new();
+        // Index into the recorder's buffer where the uncommitted window starts
+        let mut window_start = 0usize;
+
+        loop {
+            let is_final = !tap.is_recording();
+
+            let window = tap.samples_from(window_start);
+            let samples_16k = crate::audio::resample::resample(&window, tap.sample_rate, WHISPER_SAMPLE_RATE);
+            let window_ms = samples_16k.len() as u64 * 1000 / WHISPER_SAMPLE_RATE as u64;
+
+            let mut words = Vec::new();
+            if window_ms >= LIVE_MIN_WINDOW_MS {
+                let mut params = Self::full_params(language, options);
+                params.set_token_timestamps(true);
+                state.full(params, &samples_16k)
+                    .map_err(|e| format!("Whisper inference failed: {}", e))?;
+                let num_segments = state.full_n_segments()
+                    .map_err(|e| format!("Failed to get segments: {}", e))?;
+                for segment in 0..num_segments {
+                    words.extend(Self::segment_words(&state, segment, token_eot)?);
+                }
+            }
+
+            let mut committed = 0;
+            let mut commit_until_ms = None;
+            if is_final {
+                committed = words.len();
+            } else if window_ms >= LIVE_COMMIT_AFTER_MS {
+                let cut_ms = window_ms - LIVE_TAIL_MS;
+                committed = words.iter().take_while(|word| word.end_ms <= cut_ms).count();
+                commit_until_ms = match committed {
+                    // Nothing decoded at all: the window is silence, drop it
+                    0 if words.is_empty() => Some(cut_ms),
+                    0 => None,
+                    n => Some(words[n - 1].end_ms),
+                };
+            }
+
+            stable_words.extend(words[..committed].iter().map(|word| word.word.clone()));
+            let stable_text = stable_words.join(" ");
+            let text = stable_words
+                .iter()
+                .chain(words[committed..].iter().map(|word| &word.word))
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            emit(&LiveTranscript {
+                stable_text,
+                text: text.clone(),
+                is_final,
+            });
+
+            if is_final {
+                return Ok(text);
+            }
+
+            if let Some(ms) = commit_until_ms {
+                window_start += (ms * tap.sample_rate as u64 / 1000) as usize;
+            }
+
+            // Wake early when the recording stops so the final result isn't delayed
+            let started = std::time::Instant::now();
+            while started.elapsed() < LIVE_INTERVAL && tap.is_recording() {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        }
+    }
+
+    /// Group a segment's text tokens into words (a token starting with a space begins a new word)
+    fn segment_words(
+        state: &whisper_rs::WhisperState,
+        segment: i32,
+        token_eot: i32,
+    ) -> Result<Vec<TranscriptWord>, String> {
+        let num_tokens = state.full_n_tokens(segment)
+            .map_err(|e| format!("Failed to get tokens: {}", e))?;
+
+        let mut words: Vec<TranscriptWord> = Vec::new();
+        for token in 0..num_tokens {
+            let Ok(data) = state.full_get_token_data(segment, token) else {
+                continue;
+            };
+            // Skip special tokens (timestamps, language, end-of-text)
+            if data.id >= token_eot {
+                continue;
+            }
+            let Ok(piece) = state.full_get_token_text(segment, token) else {
+                continue;
+            };
+
+            // Token timestamps are in centiseconds
+            let start_ms = data.t0.max(0) as u64 * 10;
+            let end_ms = data.t1.max(0) as u64 * 10;
+            match words.last_mut() {
+                Some(word) if !piece.starts_with(' ') => {
+                    word.word.push_str(&piece);
+                    word.end_ms = word.end_ms.max(end_ms);
+                }
+                _ => {
+                    let text = piece.trim();
+                    if !text.is_empty() {
+                        words.push(TranscriptWord {
+                            word: text.to_string(),
+                            start_ms,
+                            end_ms,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(words)
+    }
+
+    /// Detect the spoken language from the start of the clip without decoding any text.
+    /// Only the mel spectrogram and whisper's language-detection pass are run, which is
+    /// much faster than a full transcription. Returns the `top_n` most likely languages.
+    pub fn detect_language(
+        model_path: &PathBuf,
+        audio_data: &[u8],
+        top_n: usize,
+    ) -> Result<Vec<LanguageCandidate>, String> {
+        let samples_16k = Self::prepare_samples(audio_data)?;
+        if samples_16k.is_empty() {
+            return Err("Audio is empty".to_string());
+        }
+
+        let (ctx, _) = Self::load_context(model_path, false)?;
+        if !ctx.is_multilingual() {
+            return Err("Model is English-only and cannot detect languages".to_string());
+        }
+
+        let mut state = ctx.create_state()
+            .map_err(|e| format!("Failed to create whisper state: {}", e))?;
+
+        let prefix_len = samples_16k.len().min(LANGUAGE_DETECTION_MS * WHISPER_SAMPLE_RATE as usize / 1000);
+        let threads = num_cpus() as usize;
+        state.pcm_to_mel(&samples_16k[..prefix_len], threads)
+            .map_err(|e| format!("Failed to compute mel spectrogram: {}", e))?;
+        let (_, probabilities) = state.lang_detect(0, threads)
+            .map_err(|e| format!("Language detection failed: {}", e))?;
+
+        let mut candidates: Vec<LanguageCandidate> = probabilities
+            .iter()
+            .enumerate()
+            .filter_map(|(id, &probability)| {
+                whisper_rs::get_lang_str(id as i32).map(|language| LanguageCandidate {
+                    language: language.to_string(),
+                    probability,
+                })
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+        candidates.truncate(top_n.max(1));
+
+        Ok(candidates)
+    }
+
+    /// Reject translation on English-only models, which would silently return a transcript
+    fn check_translate(ctx: &WhisperContext, options: &WhisperOptions) -> Result<(), String> {
+        if options.translate && !ctx.is_multilingual() {
+            return Err("Model is English-only and cannot translate; use a multilingual model".to_string());
+        }
+        Ok(())
     }
 
-    fn wav_to_f32_samples(wav_data: &[u8]) -> Result<(Vec<f32>, u32, u16), String> {
-        let cursor = std::io::Cursor::new(wav_data);
-        let reader = hound::WavReader::new(cursor)
-            .map_err(|e| format!("Failed to parse WAV: {}", e))?;
+    /// Load a model into the context cache ahead of its first transcription.
+    /// Returns the backend it was loaded on.
+    pub fn preload(model_path: &PathBuf, gpu: bool) -> Result<WhisperBackend, String> {
+        let (_, backend) = Self::load_context(model_path, gpu)?;
+        Ok(backend)
+    }
 
-        let spec = reader.spec();
-        let sample_rate = spec.sample_rate;
-        let channels = spec.channels;
+    /// Whether the model is already cached for the backend `gpu` selects
+    pub fn is_loaded(model_path: &PathBuf, gpu: bool) -> bool {
+        cache::get(model_path, Self::requested_backend(gpu)).is_some()
+    }
 
-        let samples: Vec<f32> = match spec.sample_format {
-            hound::SampleFormat::Float => {
-                reader.into_samples::<f32>()
-                    .filter_map(|s| s.ok())
-                    .collect()
+    fn requested_backend(gpu: bool) -> WhisperBackend {
+        match (gpu, WhisperBackend::gpu()) {
+            (true, Some(backend)) => backend,
+            (true, None) => {
+                eprintln!("[LocalWhisper] GPU requested, but this build has no GPU backend; using CPU");
+                WhisperBackend::Cpu
             }
-            hound::SampleFormat::Int => {
-                let bits = spec.bits_per_sample;
-                let max_val = (1u32 << (bits - 1)) as f32;
-                reader.into_samples::<i32>()
-                    .filter_map(|s| s.ok())
-                    .map(|s| s as f32 / max_val)
-                    .collect()
+            (false, _) => WhisperBackend::Cpu,
+        }
+    }
+
+    /// Load a whisper context from a model file, reusing a cached one when available.
+    /// With `gpu`, the build's GPU backend is tried first and the CPU used if it can't start.
+    fn load_context(
+        model_path: &PathBuf,
+        gpu: bool,
+    ) -> Result<(Arc<WhisperContext>, WhisperBackend), LocalTranscriptionError> {
+        let backend = Self::requested_backend(gpu);
+        if let Some(ctx) = cache::get(model_path, backend) {
+            return Ok((ctx, backend));
+        }
+
+        let path = model_path.to_str().ok_or_else(|| "Invalid model path".to_string())?;
+        let corrupt = |e: whisper_rs::WhisperError| LocalTranscriptionError::ModelCorrupt {
+            message: format!("Failed to load whisper model {}: {}", model_path.display(), e),
+        };
+        let started = std::time::Instant::now();
+        let load = |backend: WhisperBackend| {
+            // Set explicitly: GPU builds of whisper-rs default to the GPU
+            let mut params = WhisperContextParameters::default();
+            params.use_gpu(backend != WhisperBackend::Cpu);
+            WhisperContext::new_with_params(path, params)
+        };
+        let (ctx, backend) = match load(backend) {
+            Ok(ctx) => (ctx, backend),
+            Err(e) if backend != WhisperBackend::Cpu => {
+                eprintln!(
+                    "[LocalWhisper] {} initialization failed, falling back to CPU: {}",
+                    backend.as_str(),
+                    e
+                );
+                if let Some(ctx) = cache::get(model_path, WhisperBackend::Cpu) {
+                    return Ok((ctx, WhisperBackend::Cpu));
+                }
+                let ctx = load(WhisperBackend::Cpu).map_err(corrupt)?;
+                (ctx, WhisperBackend::Cpu)
             }
+            Err(e) => return Err(corrupt(e)),
         };
 
-        Ok((samples, sample_rate, channels))
+        eprintln!(
+            "[LocalWhisper] Loaded {} on {} in {} ms",
+            model_path.display(),
+            backend.as_str(),
+            started.elapsed().as_millis()
+        );
+        let ctx = Arc::new(ctx);
+        cache::insert(model_path, backend, Arc::clone(&ctx));
+        Ok((ctx, backend))
+    }
+
+    /// Decode audio (WAV, MP3, M4A, ...) and convert it to 16 kHz mono (whisper's input format)
+    fn prepare_samples(audio_data: &[u8]) -> Result<Vec<f32>, LocalTranscriptionError> {
+        let decode_failed = |message| LocalTranscriptionError::DecodeFailed { message };
+        let samples = Self::decode_to_f32_samples(audio_data).map_err(decode_failed)?;
+        Self::ensure_16khz(&samples, audio_data).map_err(decode_failed)
+    }
+
+    /// Build inference parameters shared by all transcription paths
+    fn full_params<'a>(language: Option<&'a str>, options: &WhisperOptions) -> FullParams<'a, 'a> {
+        let mut params = FullParams::new(options.sampling.to_strategy());
+
+        // Set language if provided
+        if let Some(lang) = language {
+            let lang_code = lang.split('-').next().unwrap_or(lang);
+            params.set_language(Some(lang_code));
+        } else {
+            params.set_language(Some("auto"));
+        }
+
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+        params.set_suppress_blank(true);
+        params.set_suppress_non_speech_tokens(true);
+        params.set_no_speech_thold(options.thresholds.no_speech_thold);
+        params.set_logprob_thold(options.thresholds.logprob_thold);
+        params.set_temperature_inc(options.thresholds.temperature_inc);
+        // The source language is still auto-detected (or taken from `language`) when translating
+        params.set_translate(options.translate);
+        params.set_n_threads(options.n_threads());
+        params
+    }
+
+    /// Duration of an audio clip in milliseconds
+    pub fn audio_duration_ms(audio_data: &[u8]) -> Result<u64, String> {
+        let (samples, sample_rate, channels) = Self::decode_to_f32_samples(audio_data)?;
+        if sample_rate == 0 || channels == 0 {
+            return Err("Invalid audio header".to_string());
+        }
+        let frames = samples.len() as u64 / channels as u64;
+        Ok(frames * 1000 / sample_rate as u64)
+    }
+
+    fn decode_to_f32_samples(audio_data: &[u8]) -> Result<(Vec<f32>, u32, u16), String> {
+        let decoded = crate::audio::decode::decode_audio(audio_data).map_err(|e| e.to_string())?;
+        Ok((decoded.samples, decoded.sample_rate, decoded.channels))
     }
 
     fn ensure_16khz(parsed: &(Vec<f32>, u32, u16), _raw: &[u8]) -> Result<Vec<f32>, String> {
@@ -103,28 +866,7 @@ impl LocalWhisperEngine {
         };
 
         // Resample to 16kHz if needed
-        if *sample_rate == 16000 {
-            return Ok(mono);
-        }
-
-        let ratio = 16000.0 / *sample_rate as f64;
-        let new_len = (mono.len() as f64 * ratio) as usize;
-        let mut resampled = Vec::with_capacity(new_len);
-
-        for i in 0..new_len {
-            let src_idx = i as f64 / ratio;
-            let idx = src_idx as usize;
-            let frac = src_idx - idx as f64;
-
-            if idx + 1 < mono.len() {
-                let sample = mono[idx] as f64 * (1.0 - frac) + mono[idx + 1] as f64 * frac;
-                resampled.push(sample as f32);
-            } else if idx < mono.len() {
-                resampled.push(mono[idx]);
-            }
-        }
-
-        Ok(resampled)
+        Ok(crate::audio::resample::resample(&mono, *sample_rate, WHISPER_SAMPLE_RATE))
     }
 }
 
@@ -135,3 +877,81 @@ fn num_cpus() -> i32 {
     // Use at most 4 threads for whisper to avoid hogging all CPU
     cpus.min(4)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES_PER_MS: usize = WHISPER_SAMPLE_RATE as usize / 1000;
+
+    /// Fake timestamped tokens: one every `step_ms`, each `length_ms` long, as (start, end) in the clip
+    fn tokens(clip_ms: i64, step_ms: i64, length_ms: i64) -> Vec<(i64, i64)> {
+        (0..)
+            .map(|i| (i * step_ms, i * step_ms + length_ms))
+            .take_while(|&(_, end)| end <= clip_ms)
+            .collect()
+    }
+
+    /// Indices of the tokens `transcribe_chunked` would keep, in order. A chunk only decodes
+    /// tokens that lie entirely inside it; tokens cut by its edges are lost for that chunk.
+    fn stitch(windows: &[ChunkWindow], tokens: &[(i64, i64)]) -> Vec<usize> {
+        let mut kept = Vec::new();
+        for window in windows {
+            let chunk_end_ms = (window.end / SAMPLES_PER_MS) as i64;
+            for (index, &(start, end)) in tokens.iter().enumerate() {
+                if start < window.offset_ms || end > chunk_end_ms {
+                    continue;
+                }
+                let mid_ms = (start + end) / 2 - window.offset_ms;
+                if window.keeps(mid_ms) {
+                    kept.push(index);
+                }
+            }
+        }
+        kept
+    }
+
+    #[test]
+    fn windows_cover_clip_with_overlap() {
+        let chunking = WhisperChunking::default();
+        let windows = chunking.windows(95_000 * SAMPLES_PER_MS);
+
+        let ranges: Vec<_> = windows.iter().map(|w| (w.start / SAMPLES_PER_MS, w.end / SAMPLES_PER_MS)).collect();
+        assert_eq!(ranges, vec![(0, 30_000), (28_000, 58_000), (56_000, 86_000), (84_000, 95_000)]);
+        assert_eq!(windows[0].keep_from_ms, i64::MIN);
+        assert_eq!(windows[1].keep_from_ms, 1_000);
+        assert_eq!(windows[1].keep_until_ms, 29_000);
+        assert_eq!(windows[3].keep_until_ms, i64::MAX);
+    }
+
+    #[test]
+    fn windows_for_short_clip_is_single_chunk() {
+        let windows = WhisperChunking::default().windows(10_000 * SAMPLES_PER_MS);
+        assert_eq!(windows.len(), 1);
+        assert!(windows[0].keeps(i64::MIN) && windows[0].keeps(9_999));
+    }
+
+    #[test]
+    fn stitched_tokens_have_no_duplicates_or_gaps() {
+        let clip_ms = 95_000;
+        let windows = WhisperChunking::default().windows(clip_ms as usize * SAMPLES_PER_MS);
+        // Every 50 ms, so midpoints land on the overlap centres (e.g. 29 000 ms) exactly, and
+        // 100 ms long, so tokens straddle every chunk edge
+        let tokens = tokens(clip_ms, 50, 100);
+
+        assert_eq!(stitch(&windows, &tokens), (0..tokens.len()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn stitched_tokens_with_long_words_have_no_duplicates_or_gaps() {
+        let clip_ms = 200_000;
+        let chunking = WhisperChunking {
+            chunk_length_ms: 20_000,
+            overlap_ms: 4_000,
+        };
+        let windows = chunking.windows(clip_ms as usize * SAMPLES_PER_MS);
+        let tokens = tokens(clip_ms, 130, 900);
+
+        assert_eq!(stitch(&windows, &tokens), (0..tokens.len()).collect::<Vec<_>>());
+    }
+}
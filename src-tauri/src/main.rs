@@ -12,15 +12,20 @@ static QUIT_REQUESTED: AtomicBool = AtomicBool::new(false);
 mod ai;
 mod audio;
 mod commands;
+mod hotkey;
+mod http_client;
 mod local_models;
+mod permissions;
 mod secure_storage;
+mod settings;
+mod window_state;
 
 use commands::AppState;
 
 #[tokio::main]
 async fn main() {
     // Initialize AI Proxy
-    let ai_proxy = Arc::new(ai::AIProxy::new());
+    let ai_proxy = Arc::new(ai::AIProxy::new(ai::RetryConfig::default()));
 
     // Initialize Audio Recording Manager
     let audio_manager = Arc::new(audio::AudioRecordingManager::new());
@@ -53,37 +58,99 @@ async fn main() {
         .invoke_handler(tauri::generate_handler![
             // AI commands - credentials passed per-request
             commands::chat_completion,
+            commands::chat_completion_variants,
+            commands::chat_completion_with_tools,
             commands::chat_completion_stream,
+            commands::mcp_connect,
+            commands::mcp_disconnect,
+            commands::mcp_list_tools,
             commands::fetch_provider_models,
+            commands::test_provider_credentials,
+            commands::get_request_defaults,
+            commands::set_request_defaults,
+            commands::set_rate_limit,
+            commands::rate_limit_status,
+            commands::export_conversation,
+            commands::estimate_tokens,
+            commands::get_tls_settings,
+            commands::set_tls_settings,
+            commands::set_network_proxy,
+            commands::set_request_logging,
+            commands::set_response_cache,
             // AI Audio commands - credentials passed per-request
             commands::transcribe_audio,
+            commands::transcribe_audio_verbose,
+            commands::transcribe_audio_stream,
+            commands::transcribe_and_refine,
+            commands::dictate,
+            commands::generate_image,
             commands::text_to_speech,
+            commands::text_to_speech_stream,
+            commands::chat_stream_and_speak,
             // Abort operations
             commands::abort_operation,
             // Secure storage commands
             secure_storage::secure_storage_set,
             secure_storage::secure_storage_get,
             secure_storage::secure_storage_delete,
+            secure_storage::secure_storage_delete_by_prefix,
             secure_storage::secure_storage_has,
+            secure_storage::secure_storage_get_metadata,
+            secure_storage::secure_storage_list_keys,
+            secure_storage::export_credentials,
+            secure_storage::import_credentials,
             secure_storage::secure_storage_set_provider_keys,
             secure_storage::secure_storage_get_provider_keys,
             // Keyboard simulation
             commands::simulate_paste,
+            commands::paste_text,
+            commands::simulate_type_text,
+            // Global dictation hotkey
+            commands::register_dictation_hotkey,
+            commands::unregister_dictation_hotkey,
             // Audio recording commands
             commands::start_audio_recording,
             commands::stop_audio_recording,
             commands::cancel_audio_recording,
+            commands::pause_audio_recording,
+            commands::resume_audio_recording,
             commands::reset_audio_recording,
+            commands::current_default_input_device,
+            commands::list_audio_input_devices,
+            commands::get_recording_defaults,
+            commands::set_recording_defaults,
+            commands::set_audio_pre_roll,
+            commands::record_fixed,
+            commands::audio_to_data_uri,
+            commands::trim_audio,
+            commands::resample_audio,
+            commands::audio_waveform,
+            commands::apply_high_pass,
             // Local model commands
             // System settings
             commands::open_accessibility_settings,
+            commands::check_microphone_permission,
+            commands::request_microphone_permission,
             // Notification sound
             commands::play_notification_sound,
             // Local model commands
             commands::local_models_list,
+            commands::local_model_catalog,
+            commands::refresh_model_catalog,
+            commands::add_custom_model,
             commands::local_model_download,
             commands::local_model_delete,
             commands::local_transcribe_audio,
+            commands::local_transcribe_audio_segments,
+            commands::detect_audio_language,
+            commands::start_live_transcription,
+            commands::start_cloud_live_transcription,
+            commands::whisper_cache_status,
+            commands::set_whisper_cache_budget,
+            commands::clear_whisper_cache,
+            commands::unload_local_model,
+            commands::preload_local_model,
+            commands::benchmark_local_models,
         ])
         .setup(|app| {
             // Initialize Secure Storage with app data directory
@@ -91,11 +158,55 @@ async fn main() {
                 .expect("Failed to get app data directory");
             let secure_storage = secure_storage::SecureStorage::new(app_data_dir.clone());
 
+            // Restore the opt-in request audit log
+            if let Some(logging) = settings::load::<ai::audit::RequestLogSettings>(app.handle(), settings::REQUEST_LOGGING_KEY) {
+                ai::audit::set_request_logging(logging, &app_data_dir);
+            }
+
+            // Restore the response cache for deterministic completions
+            if let Some(cache) = settings::load::<ai::response_cache::ResponseCacheSettings>(app.handle(), settings::RESPONSE_CACHE_KEY) {
+                app.state::<AppState>().ai_proxy.set_response_cache(cache, &app_data_dir);
+            }
+
             // Initialize Local Model Manager
             let local_model_manager = Arc::new(local_models::LocalModelManager::new(app_data_dir));
             app.manage(secure_storage);
             app.manage(local_model_manager);
 
+            // Restore persisted chat request defaults
+            if let Some(defaults) = settings::load::<ai::RequestDefaults>(app.handle(), settings::REQUEST_DEFAULTS_KEY) {
+                app.state::<AppState>().ai_proxy.set_request_defaults(defaults);
+            }
+
+            // Restore request throttling
+            if let Some(requests_per_minute) = settings::load::<u32>(app.handle(), settings::RATE_LIMIT_KEY) {
+                app.state::<AppState>().ai_proxy.set_rate_limit(Some(requests_per_minute));
+            }
+
+            // Restore the whisper context cache budget
+            if let Some(budget_mb) = settings::load::<u64>(app.handle(), settings::WHISPER_CACHE_BUDGET_KEY) {
+                local_models::cache::set_budget_mb(budget_mb);
+            }
+
+            // Restore TLS settings for self-hosted endpoints
+            if let Some(tls) = settings::load::<http_client::TlsSettings>(app.handle(), settings::TLS_SETTINGS_KEY) {
+                if let Err(e) = http_client::set_tls_settings(tls) {
+                    eprintln!("[Setup] Failed to apply TLS settings: {}", e);
+                }
+            }
+
+            // Restore the network proxy (otherwise the proxy environment variables apply)
+            if let Some(proxy) = settings::load::<String>(app.handle(), settings::NETWORK_PROXY_KEY) {
+                if let Err(e) = http_client::set_network_proxy(Some(&proxy)) {
+                    eprintln!("[Setup] Failed to apply network proxy: {}", e);
+                }
+            }
+
+            // Reopen the main window where it was left, kept on a connected monitor
+            if let Some(window) = app.get_webview_window("main") {
+                window_state::restore(&window);
+            }
+
             // Create tray menu items
             let show_item = MenuItemBuilder::with_id("show", "Pokaż").build(app)?;
             let quit_item = MenuItemBuilder::with_id("quit", "Zamknij").build(app)?;
@@ -142,7 +253,8 @@ async fn main() {
 
             // Note: Microphone permissions are handled by the OS
             // On macOS: Info.plist includes NSMicrophoneUsageDescription
-            // The system will show a permission dialog on first microphone access
+            // The frontend checks/requests access up front via check_microphone_permission
+            // and request_microphone_permission; otherwise the dialog shows on first access
             Ok(())
         })
         .on_window_event(|window, event| {
@@ -157,6 +269,11 @@ async fn main() {
                         api.prevent_close();
                     }
                 }
+                tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+                    if label == "main" {
+                        window_state::schedule_save(window);
+                    }
+                }
                 _ => {}
             }
         })
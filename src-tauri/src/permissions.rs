@@ -0,0 +1,70 @@
+use serde::Serialize;
+
+/// Microphone access for this app, as reported by the OS
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MicrophonePermission {
+    Granted,
+    /// Denied by the user or restricted by policy; only System Settings can change it
+    Denied,
+    /// The user hasn't been asked yet; recording now would capture silence until they answer
+    Undetermined,
+    /// The OS doesn't gate microphone access per app (Windows, Linux)
+    NotApplicable,
+}
+
+#[cfg(target_os = "macos")]
+pub fn microphone_status() -> MicrophonePermission {
+    use objc2_av_foundation::{AVAuthorizationStatus, AVCaptureDevice, AVMediaTypeAudio};
+
+    let Some(media_type) = (unsafe { AVMediaTypeAudio }) else {
+        return MicrophonePermission::Undetermined;
+    };
+    match unsafe { AVCaptureDevice::authorizationStatusForMediaType(media_type) } {
+        AVAuthorizationStatus::Authorized => MicrophonePermission::Granted,
+        AVAuthorizationStatus::NotDetermined => MicrophonePermission::Undetermined,
+        _ => MicrophonePermission::Denied,
+    }
+}
+
+/// Show the system microphone prompt if the user hasn't answered it yet, and wait for the answer.
+/// Once answered, macOS never prompts again and the current status is returned.
+#[cfg(target_os = "macos")]
+pub async fn request_microphone() -> MicrophonePermission {
+    use objc2::runtime::Bool;
+    use objc2_av_foundation::{AVCaptureDevice, AVMediaTypeAudio};
+
+    let status = microphone_status();
+    if status != MicrophonePermission::Undetermined {
+        return status;
+    }
+    let Some(media_type) = (unsafe { AVMediaTypeAudio }) else {
+        return status;
+    };
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    // The completion handler is called once, but blocks must be `Fn`
+    let tx = std::sync::Mutex::new(Some(tx));
+    let handler = block2::RcBlock::new(move |granted: Bool| {
+        if let Some(tx) = tx.lock().ok().and_then(|mut pending| pending.take()) {
+            let _ = tx.send(granted.as_bool());
+        }
+    });
+    unsafe { AVCaptureDevice::requestAccessForMediaType_completionHandler(media_type, &handler) };
+
+    match rx.await {
+        Ok(true) => MicrophonePermission::Granted,
+        Ok(false) => MicrophonePermission::Denied,
+        Err(_) => microphone_status(),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn microphone_status() -> MicrophonePermission {
+    MicrophonePermission::NotApplicable
+}
+
+#[cfg(not(target_os = "macos"))]
+pub async fn request_microphone() -> MicrophonePermission {
+    MicrophonePermission::NotApplicable
+}
@@ -1,16 +1,27 @@
 use aes_gcm::{
-    aead::{Aead, AeadCore, KeyInit, OsRng},
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
     Aes256Gcm, Nonce,
 };
-use serde::Serialize;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use std::sync::Mutex;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::State;
 
 const STORAGE_FILE: &str = "secure_credentials.enc";
+/// OS keychain entry holding the base64-encoded master key
+const KEYCHAIN_SERVICE: &str = "com.assistant.app";
+const KEYCHAIN_USER: &str = "secure-storage-master-key";
+/// Format version of exported credential blobs
+const EXPORT_VERSION: u32 = 1;
+const MIN_PASSPHRASE_LEN: usize = 8;
+/// Namespace of provider API keys (`provider_<uuid>`)
+pub const PROVIDER_KEY_PREFIX: &str = "provider_";
 
 #[derive(Debug, thiserror::Error)]
 pub enum SecureStorageError {
@@ -22,6 +33,8 @@ pub enum SecureStorageError {
     Serialization(#[from] serde_json::Error),
     #[error("Credential not found for key: {0}")]
     NotFound(String),
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
 }
 
 impl Serialize for SecureStorageError {
@@ -33,89 +46,298 @@ impl Serialize for SecureStorageError {
     }
 }
 
+/// On-disk shape of a stored credential
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredCredential {
+    value: String,
+    /// Unix epoch ms
+    created_at: u64,
+    /// Unix epoch ms
+    updated_at: u64,
+}
+
+/// Older files stored plain strings; both shapes are read and plain ones are migrated
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredEntry {
+    Credential(StoredCredential),
+    Plain(String),
+}
+
+/// Timestamps of a stored credential (Unix epoch ms)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialMetadata {
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Legacy key derived from the device name. Anyone who knows the device name can
+/// recompute it, so it is only used when the OS keychain is unavailable.
+fn device_derived_key() -> [u8; 32] {
+    let machine_id = whoami::devicename();
+    let mut hasher = Sha256::new();
+    hasher.update(machine_id.as_bytes());
+    hasher.update(b"com.assistant.app.secret"); // App-specific salt
+    let hash = hasher.finalize();
+
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&hash[..]);
+    key
+}
+
+/// Random master key kept in the OS keychain, created on first use
+fn keychain_key() -> Result<[u8; 32], String> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_USER).map_err(|e| e.to_string())?;
+
+    match entry.get_password() {
+        Ok(encoded) => {
+            let bytes = BASE64.decode(encoded.trim()).map_err(|e| e.to_string())?;
+            bytes
+                .try_into()
+                .map_err(|_| "Keychain master key has an invalid length".to_string())
+        }
+        Err(keyring::Error::NoEntry) => {
+            let mut key = [0u8; 32];
+            key.copy_from_slice(&Aes256Gcm::generate_key(&mut OsRng));
+            entry.set_password(&BASE64.encode(key)).map_err(|e| e.to_string())?;
+            Ok(key)
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Portable credential export, encrypted under a passphrase-derived key (Argon2id)
+#[derive(Serialize, Deserialize)]
+struct CredentialExport {
+    version: u32,
+    /// Base64 Argon2 salt
+    salt: String,
+    /// Base64 nonce-prefixed AES-256-GCM ciphertext of the credential map
+    data: String,
+}
+
+fn passphrase_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], SecureStorageError> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| SecureStorageError::Encryption(format!("Key derivation failed: {}", e)))?;
+    Ok(key)
+}
+
+/// Encrypt into a nonce-prefixed AES-256-GCM blob
+fn encrypt(key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>, SecureStorageError> {
+    let cipher = Aes256Gcm::new(key.into());
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|e| SecureStorageError::Encryption(format!("Encryption failed: {}", e)))?;
+
+    // Prepend nonce to ciphertext
+    let mut encrypted_data = nonce.to_vec();
+    encrypted_data.extend_from_slice(&ciphertext);
+    Ok(encrypted_data)
+}
+
+/// Decrypt a nonce-prefixed AES-256-GCM blob
+fn decrypt(key: &[u8; 32], encrypted_data: &[u8]) -> Result<Vec<u8>, SecureStorageError> {
+    if encrypted_data.len() < 12 {
+        return Err(SecureStorageError::Encryption("Encrypted data is too short".to_string()));
+    }
+
+    let cipher = Aes256Gcm::new(key.into());
+    let (nonce_bytes, ciphertext) = encrypted_data.split_at(12);
+    let nonce = Nonce::from_slice(nonce_bytes);
+
+    cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|e| SecureStorageError::Encryption(format!("Decryption failed: {}", e)))
+}
+
 pub struct SecureStorage {
     storage_path: PathBuf,
     cache: Mutex<HashMap<String, String>>,
     encryption_key: [u8; 32],
+    /// Device-derived key files may still be encrypted with; set only when the
+    /// keychain key is in use, so such files are re-encrypted on first load
+    legacy_key: Option<[u8; 32]>,
+    /// Serializes read-modify-write cycles so concurrent commands don't drop each other's changes
+    write_lock: Mutex<()>,
 }
 
 impl SecureStorage {
     pub fn new(app_data_dir: PathBuf) -> Self {
-        // Generate encryption key from machine-specific data
-        // In production, you might want to use a more sophisticated key derivation
-        let machine_id = whoami::devicename();
-        let mut hasher = Sha256::new();
-        hasher.update(machine_id.as_bytes());
-        hasher.update(b"com.assistant.app.secret"); // App-specific salt
-        let hash = hasher.finalize();
-
-        let mut key = [0u8; 32];
-        key.copy_from_slice(&hash[..]);
-
-        Self {
+        let (encryption_key, legacy_key) = match keychain_key() {
+            Ok(key) => (key, Some(device_derived_key())),
+            Err(e) => {
+                eprintln!("[SecureStorage] OS keychain unavailable ({}), using device-derived key", e);
+                (device_derived_key(), None)
+            }
+        };
+
+        let storage = Self {
             storage_path: app_data_dir.join(STORAGE_FILE),
             cache: Mutex::new(HashMap::new()),
-            encryption_key: key,
-        }
+            encryption_key,
+            legacy_key,
+            write_lock: Mutex::new(()),
+        };
+        storage.migrate();
+        storage
     }
 
-    fn load_credentials(&self) -> Result<HashMap<String, String>, SecureStorageError> {
-        if !self.storage_path.exists() {
-            return Ok(HashMap::new());
-        }
-
-        let encrypted_data = fs::read(&self.storage_path)?;
-        if encrypted_data.is_empty() {
-            return Ok(HashMap::new());
+    /// Rewrite a store that is still under the legacy key or has plain entries. Done once here,
+    /// under the write lock, so reads never write and can't overwrite a concurrent change.
+    fn migrate(&self) {
+        let _guard = self.lock_writes();
+        let result = self.read_store(&self.storage_path).and_then(|(credentials, migrated)| {
+            if !migrated {
+                return Ok(());
+            }
+            eprintln!("[SecureStorage] Migrating credentials to the current format and key");
+            self.save_credentials(&credentials)
+        });
+        if let Err(e) = result {
+            eprintln!("[SecureStorage] Credential migration failed: {}", e);
         }
+    }
 
-        // Decrypt
-        let cipher = Aes256Gcm::new((&self.encryption_key).into());
+    fn backup_path(&self) -> PathBuf {
+        self.storage_path.with_extension("bak")
+    }
 
-        // First 12 bytes are nonce
-        if encrypted_data.len() < 12 {
-            return Ok(HashMap::new());
+    fn lock_writes(&self) -> MutexGuard<'_, ()> {
+        match self.write_lock.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
         }
+    }
 
-        let (nonce_bytes, ciphertext) = encrypted_data.split_at(12);
-        let nonce = Nonce::from_slice(nonce_bytes);
+    /// Decrypt a store file; the flag is set when it was still under the legacy key
+    fn decrypt_store(&self, encrypted_data: &[u8]) -> Result<(Vec<u8>, bool), SecureStorageError> {
+        match decrypt(&self.encryption_key, encrypted_data) {
+            Ok(decrypted) => Ok((decrypted, false)),
+            // Files written before the keychain key existed are still under the legacy key
+            Err(e) => match self.legacy_key.as_ref().and_then(|key| decrypt(key, encrypted_data).ok()) {
+                Some(decrypted) => Ok((decrypted, true)),
+                None => Err(e),
+            },
+        }
+    }
 
-        let decrypted = cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|e| SecureStorageError::Encryption(format!("Decryption failed: {}", e)))?;
+    /// Load the store, falling back to the `.bak` copy when the main file is corrupted
+    fn load_credentials(&self) -> Result<HashMap<String, StoredCredential>, SecureStorageError> {
+        match self.load_from(&self.storage_path) {
+            Ok(credentials) => Ok(credentials),
+            Err(e) => {
+                let backup = self.backup_path();
+                if !backup.exists() {
+                    return Err(e);
+                }
+                eprintln!("[SecureStorage] Credential file unreadable ({}), loading backup", e);
+                self.load_from(&backup).map_err(|_| e)
+            }
+        }
+    }
 
-        let credentials: HashMap<String, String> = serde_json::from_slice(&decrypted)?;
-        Ok(credentials)
+    fn load_from(&self, path: &Path) -> Result<HashMap<String, StoredCredential>, SecureStorageError> {
+        self.read_store(path).map(|(credentials, _)| credentials)
     }
 
-    fn save_credentials(&self, credentials: &HashMap<String, String>) -> Result<(), SecureStorageError> {
-        // Serialize
-        let json_data = serde_json::to_vec(credentials)?;
+    /// Read a store file; the flag is set when it needs migrating (legacy key or plain entries)
+    fn read_store(&self, path: &Path) -> Result<(HashMap<String, StoredCredential>, bool), SecureStorageError> {
+        if !path.exists() {
+            return Ok((HashMap::new(), false));
+        }
 
-        // Encrypt
-        let cipher = Aes256Gcm::new((&self.encryption_key).into());
-        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let encrypted_data = fs::read(path)?;
+        if encrypted_data.is_empty() {
+            return Ok((HashMap::new(), false));
+        }
 
-        let ciphertext = cipher
-            .encrypt(&nonce, json_data.as_ref())
-            .map_err(|e| SecureStorageError::Encryption(format!("Encryption failed: {}", e)))?;
+        let (decrypted, mut migrated) = self.decrypt_store(&encrypted_data)?;
+
+        let entries: HashMap<String, StoredEntry> = serde_json::from_slice(&decrypted)?;
+
+        // Plain entries predate timestamps; the file's modification time is the best guess
+        let migrated_at = fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or_else(now_ms);
+
+        let credentials: HashMap<String, StoredCredential> = entries
+            .into_iter()
+            .map(|(key, entry)| {
+                let credential = match entry {
+                    StoredEntry::Credential(credential) => credential,
+                    StoredEntry::Plain(value) => {
+                        migrated = true;
+                        StoredCredential {
+                            value,
+                            created_at: migrated_at,
+                            updated_at: migrated_at,
+                        }
+                    }
+                };
+                (key, credential)
+            })
+            .collect();
+
+        Ok((credentials, migrated))
+    }
 
-        // Prepend nonce to ciphertext
-        let mut encrypted_data = nonce.to_vec();
-        encrypted_data.extend_from_slice(&ciphertext);
+    fn save_credentials(&self, credentials: &HashMap<String, StoredCredential>) -> Result<(), SecureStorageError> {
+        // Serialize and encrypt
+        let json_data = serde_json::to_vec(credentials)?;
+        let encrypted_data = encrypt(&self.encryption_key, &json_data)?;
 
         // Ensure directory exists
         if let Some(parent) = self.storage_path.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        fs::write(&self.storage_path, encrypted_data)?;
+        // Keep the last readable version as a backup before replacing it
+        let current_is_valid = fs::read(&self.storage_path)
+            .ok()
+            .is_some_and(|data| self.decrypt_store(&data).is_ok());
+        if current_is_valid {
+            fs::copy(&self.storage_path, self.backup_path())?;
+        }
+
+        // Write a temp file in the same directory and rename it over the target, so a crash
+        // mid-write never leaves a truncated store
+        let temp_path = self.storage_path.with_extension("tmp");
+        {
+            let mut file = fs::File::create(&temp_path)?;
+            file.write_all(&encrypted_data)?;
+            file.sync_all()?;
+        }
+        fs::rename(&temp_path, &self.storage_path)?;
         Ok(())
     }
 
     pub fn set_credential(&self, key: &str, value: &str) -> Result<(), SecureStorageError> {
+        let _guard = self.lock_writes();
         let mut credentials = self.load_credentials()?;
-        credentials.insert(key.to_string(), value.to_string());
+        let now = now_ms();
+        let created_at = credentials.get(key).map(|existing| existing.created_at).unwrap_or(now);
+        credentials.insert(
+            key.to_string(),
+            StoredCredential {
+                value: value.to_string(),
+                created_at,
+                updated_at: now,
+            },
+        );
         self.save_credentials(&credentials)?;
 
         // Update cache
@@ -137,18 +359,31 @@ impl SecureStorage {
         // Load from file
         let credentials = self.load_credentials()?;
         match credentials.get(key) {
-            Some(value) => {
+            Some(credential) => {
                 // Update cache
                 if let Ok(mut cache) = self.cache.lock() {
-                    cache.insert(key.to_string(), value.clone());
+                    cache.insert(key.to_string(), credential.value.clone());
                 }
-                Ok(value.clone())
+                Ok(credential.value.clone())
             }
             None => Err(SecureStorageError::NotFound(key.to_string())),
         }
     }
 
+    /// When a credential was first stored and last changed
+    pub fn get_credential_metadata(&self, key: &str) -> Result<CredentialMetadata, SecureStorageError> {
+        let credentials = self.load_credentials()?;
+        credentials
+            .get(key)
+            .map(|credential| CredentialMetadata {
+                created_at: credential.created_at,
+                updated_at: credential.updated_at,
+            })
+            .ok_or_else(|| SecureStorageError::NotFound(key.to_string()))
+    }
+
     pub fn delete_credential(&self, key: &str) -> Result<(), SecureStorageError> {
+        let _guard = self.lock_writes();
         let mut credentials = self.load_credentials()?;
         credentials.remove(key);
         self.save_credentials(&credentials)?;
@@ -161,6 +396,95 @@ impl SecureStorage {
         Ok(())
     }
 
+    /// Delete every credential whose key starts with `prefix` in a single save.
+    /// Returns how many were deleted.
+    pub fn delete_credentials_by_prefix(&self, prefix: &str) -> Result<usize, SecureStorageError> {
+        // An empty prefix would match, and wipe, the whole store
+        if prefix.is_empty() {
+            return Err(SecureStorageError::InvalidInput("Prefix must not be empty".to_string()));
+        }
+
+        let _guard = self.lock_writes();
+        let mut credentials = self.load_credentials()?;
+        let before = credentials.len();
+        credentials.retain(|key, _| !key.starts_with(prefix));
+        let deleted = before - credentials.len();
+        if deleted == 0 {
+            return Ok(0);
+        }
+        self.save_credentials(&credentials)?;
+
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.retain(|key, _| !key.starts_with(prefix));
+        }
+
+        Ok(deleted)
+    }
+
+    /// Keys of all stored credentials, sorted (values are never returned)
+    pub fn list_credential_keys(&self) -> Result<Vec<String>, SecureStorageError> {
+        let mut keys: Vec<String> = self.load_credentials()?.into_keys().collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    /// Export every credential as a portable blob encrypted under `passphrase`,
+    /// independent of this machine's storage key
+    pub fn export_credentials(&self, passphrase: &str) -> Result<String, SecureStorageError> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+            return Err(SecureStorageError::Encryption(format!(
+                "Passphrase must be at least {} characters",
+                MIN_PASSPHRASE_LEN
+            )));
+        }
+
+        let credentials = self.load_credentials()?;
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let key = passphrase_key(passphrase, &salt)?;
+        let data = encrypt(&key, &serde_json::to_vec(&credentials)?)?;
+
+        let export = CredentialExport {
+            version: EXPORT_VERSION,
+            salt: BASE64.encode(salt),
+            data: BASE64.encode(data),
+        };
+        Ok(BASE64.encode(serde_json::to_vec(&export)?))
+    }
+
+    /// Merge credentials from an exported blob into the store; imported entries replace
+    /// existing ones with the same key. Returns the number of imported credentials.
+    pub fn import_credentials(&self, blob: &str, passphrase: &str) -> Result<usize, SecureStorageError> {
+        let invalid = |what: &str| SecureStorageError::Encryption(format!("Invalid credential export: {}", what));
+
+        let envelope = BASE64.decode(blob.trim()).map_err(|_| invalid("not base64"))?;
+        let export: CredentialExport = serde_json::from_slice(&envelope)?;
+        if export.version != EXPORT_VERSION {
+            return Err(invalid(&format!("unsupported version {}", export.version)));
+        }
+
+        let salt = BASE64.decode(&export.salt).map_err(|_| invalid("bad salt"))?;
+        let data = BASE64.decode(&export.data).map_err(|_| invalid("bad data"))?;
+        let key = passphrase_key(passphrase, &salt)?;
+        let decrypted = decrypt(&key, &data).map_err(|_| {
+            SecureStorageError::Encryption("Wrong passphrase or corrupted export".to_string())
+        })?;
+        let imported: HashMap<String, StoredCredential> = serde_json::from_slice(&decrypted)?;
+        let count = imported.len();
+
+        let _guard = self.lock_writes();
+        let mut credentials = self.load_credentials()?;
+        if let Ok(mut cache) = self.cache.lock() {
+            for (key, credential) in &imported {
+                cache.insert(key.clone(), credential.value.clone());
+            }
+        }
+        credentials.extend(imported);
+        self.save_credentials(&credentials)?;
+
+        Ok(count)
+    }
+
     pub fn has_credential(&self, key: &str) -> bool {
         // Check cache first
         if let Ok(cache) = self.cache.lock() {
@@ -205,6 +529,23 @@ pub fn secure_storage_delete(
     storage.delete_credential(&key)
 }
 
+/// Delete all credentials whose key starts with `prefix` (e.g. "provider_"); returns the count deleted
+#[tauri::command]
+pub fn secure_storage_delete_by_prefix(
+    storage: State<'_, SecureStorage>,
+    prefix: String,
+) -> Result<usize, SecureStorageError> {
+    storage.delete_credentials_by_prefix(&prefix)
+}
+
+#[tauri::command]
+pub fn secure_storage_get_metadata(
+    storage: State<'_, SecureStorage>,
+    key: String,
+) -> Result<CredentialMetadata, SecureStorageError> {
+    storage.get_credential_metadata(&key)
+}
+
 #[tauri::command]
 pub fn secure_storage_has(
     storage: State<'_, SecureStorage>,
@@ -213,6 +554,33 @@ pub fn secure_storage_has(
     Ok(storage.has_credential(&key))
 }
 
+/// List stored credential keys without their values
+#[tauri::command]
+pub fn secure_storage_list_keys(
+    storage: State<'_, SecureStorage>,
+) -> Result<Vec<String>, SecureStorageError> {
+    storage.list_credential_keys()
+}
+
+/// Export all credentials as a blob encrypted under `passphrase`, for moving keys between machines
+#[tauri::command]
+pub fn export_credentials(
+    storage: State<'_, SecureStorage>,
+    passphrase: String,
+) -> Result<String, SecureStorageError> {
+    storage.export_credentials(&passphrase)
+}
+
+/// Merge credentials from an `export_credentials` blob; returns how many were imported
+#[tauri::command]
+pub fn import_credentials(
+    storage: State<'_, SecureStorage>,
+    blob: String,
+    passphrase: String,
+) -> Result<usize, SecureStorageError> {
+    storage.import_credentials(&blob, &passphrase)
+}
+
 /// Store multiple provider API keys at once
 #[tauri::command]
 pub fn secure_storage_set_provider_keys(
@@ -220,7 +588,7 @@ pub fn secure_storage_set_provider_keys(
     provider_keys: HashMap<String, String>,
 ) -> Result<(), SecureStorageError> {
     for (provider_uuid, api_key) in &provider_keys {
-        let key = format!("provider_{}", provider_uuid);
+        let key = format!("{}{}", PROVIDER_KEY_PREFIX, provider_uuid);
         storage.set_credential(&key, api_key)?;
     }
     Ok(())
@@ -235,7 +603,7 @@ pub fn secure_storage_get_provider_keys(
     let mut result = HashMap::new();
 
     for uuid in &provider_uuids {
-        let key = format!("provider_{}", uuid);
+        let key = format!("{}{}", PROVIDER_KEY_PREFIX, uuid);
         if let Ok(api_key) = storage.get_credential(&key) {
             result.insert(uuid.clone(), api_key);
         }
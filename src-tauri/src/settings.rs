@@ -0,0 +1,36 @@
+use serde::{de::DeserializeOwned, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+/// Store file for backend-owned settings (kept separate from the frontend's store)
+const SETTINGS_STORE: &str = "backend-settings.json";
+
+pub const REQUEST_DEFAULTS_KEY: &str = "request_defaults";
+pub const RECORDING_DEFAULTS_KEY: &str = "recording_defaults";
+pub const TLS_SETTINGS_KEY: &str = "tls_settings";
+pub const NETWORK_PROXY_KEY: &str = "network_proxy";
+pub const REQUEST_LOGGING_KEY: &str = "request_logging";
+pub const RESPONSE_CACHE_KEY: &str = "response_cache";
+pub const RATE_LIMIT_KEY: &str = "rate_limit_rpm";
+pub const WHISPER_CACHE_BUDGET_KEY: &str = "whisper_cache_budget_mb";
+pub const WINDOW_STATE_KEY: &str = "main_window_state";
+
+/// Read a persisted setting, returning None if missing or unreadable
+pub fn load<T: DeserializeOwned>(app: &AppHandle, key: &str) -> Option<T> {
+    let store = app.store(SETTINGS_STORE).ok()?;
+    let value = store.get(key)?;
+    serde_json::from_value(value).ok()
+}
+
+/// Persist a setting and flush the store to disk
+pub fn save<T: Serialize>(app: &AppHandle, key: &str, value: &T) -> Result<(), String> {
+    let store = app
+        .store(SETTINGS_STORE)
+        .map_err(|e| format!("Failed to open settings store: {}", e))?;
+    let json = serde_json::to_value(value)
+        .map_err(|e| format!("Failed to serialize setting {}: {}", key, e))?;
+    store.set(key, json);
+    store
+        .save()
+        .map_err(|e| format!("Failed to save settings: {}", e))
+}
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{Monitor, PhysicalPosition, PhysicalSize, Runtime, WebviewWindow, Window};
+use crate::settings;
+
+/// Moves and resizes arrive in bursts while dragging; only the last one in this window is saved
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+/// Smallest size a restored window is given, so a bad entry can't leave it unusable
+const MIN_WIDTH: u32 = 320;
+const MIN_HEIGHT: u32 = 240;
+
+/// Main window geometry in physical pixels: outer position, inner size
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct WindowGeometry {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    #[serde(default)]
+    maximized: bool,
+}
+
+static SAVE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Apply the saved geometry, pulled back onto a connected monitor if it no longer fits on any
+pub fn restore<R: Runtime>(window: &WebviewWindow<R>) {
+    let Some(saved) = settings::load::<WindowGeometry>(window.app_handle(), settings::WINDOW_STATE_KEY) else {
+        return;
+    };
+    let monitors = window.available_monitors().unwrap_or_default();
+    let primary = window.primary_monitor().ok().flatten();
+    let Some(geometry) = clamp_to_monitors(saved, &monitors, primary.as_ref()) else {
+        return;
+    };
+
+    if let Err(e) = window.set_size(PhysicalSize::new(geometry.width, geometry.height)) {
+        eprintln!("[Window] Failed to restore size: {}", e);
+    }
+    if let Err(e) = window.set_position(PhysicalPosition::new(geometry.x, geometry.y)) {
+        eprintln!("[Window] Failed to restore position: {}", e);
+    }
+    if geometry.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Schedule a save of the window's current geometry (debounced)
+pub fn schedule_save<R: Runtime>(window: &Window<R>) {
+    let generation = SAVE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let window = window.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DEBOUNCE).await;
+        if SAVE_GENERATION.load(Ordering::SeqCst) == generation {
+            save(&window);
+        }
+    });
+}
+
+fn save<R: Runtime>(window: &Window<R>) {
+    // Minimized windows report off-screen placeholder coordinates
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+
+    let maximized = window.is_maximized().unwrap_or(false);
+    let geometry = if maximized {
+        // Keep the restored (un-maximized) bounds so un-maximizing after restart still works
+        match settings::load::<WindowGeometry>(window.app_handle(), settings::WINDOW_STATE_KEY) {
+            Some(previous) => WindowGeometry { maximized: true, ..previous },
+            None => return,
+        }
+    } else {
+        let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+            return;
+        };
+        WindowGeometry {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+            maximized: false,
+        }
+    };
+
+    if let Err(e) = settings::save(window.app_handle(), settings::WINDOW_STATE_KEY, &geometry) {
+        eprintln!("[Window] Failed to save window state: {}", e);
+    }
+}
+
+/// Fit the geometry onto the monitor it overlaps most, or the primary monitor if it overlaps
+/// none (e.g. it was saved on a display that has since been unplugged). The size is shrunk to
+/// the monitor and the position moved so the whole window is visible.
+fn clamp_to_monitors(
+    geometry: WindowGeometry,
+    monitors: &[Monitor],
+    primary: Option<&Monitor>,
+) -> Option<WindowGeometry> {
+    let overlap = |monitor: &Monitor| {
+        let (mx, my) = (monitor.position().x as i64, monitor.position().y as i64);
+        let (mw, mh) = (monitor.size().width as i64, monitor.size().height as i64);
+        let width = (geometry.x as i64 + geometry.width as i64).min(mx + mw) - (geometry.x as i64).max(mx);
+        let height = (geometry.y as i64 + geometry.height as i64).min(my + mh) - (geometry.y as i64).max(my);
+        width.max(0) * height.max(0)
+    };
+
+    let monitor = monitors
+        .iter()
+        .map(|monitor| (overlap(monitor), monitor))
+        .filter(|(area, _)| *area > 0)
+        .max_by_key(|(area, _)| *area)
+        .map(|(_, monitor)| monitor)
+        .or(primary)
+        .or(monitors.first())?;
+
+    let (mx, my) = (monitor.position().x, monitor.position().y);
+    let (mw, mh) = (monitor.size().width, monitor.size().height);
+    let width = geometry.width.clamp(MIN_WIDTH.min(mw), mw);
+    let height = geometry.height.clamp(MIN_HEIGHT.min(mh), mh);
+
+    Some(WindowGeometry {
+        x: geometry.x.clamp(mx, mx + (mw - width) as i32),
+        y: geometry.y.clamp(my, my + (mh - height) as i32),
+        width,
+        height,
+        maximized: geometry.maximized,
+    })
+}
@@ -2,6 +2,7 @@ import {G} from "../../appInitializer/module/G.ts";
 import {store} from "../../appInitializer/store";
 import {Logger} from "../../logger/Logger.ts";
 import {ProviderCredentials} from "../../rustProxy/interface/AITypes.ts";
+import {LocalTranscriptionFailure} from "../../rustProxy/RustProxy.ts";
 import {getRandomId} from "../../utils/dataGenerator.ts";
 import {createCompositeModelId, parseModelId} from "./interface/AIModel.ts";
 import {AIModelConfig} from "./interface/AIModelConfig.ts";
@@ -162,7 +163,7 @@ export class AIServiceBackend {
             prompt: string;
         },
         operationId?: string,
-    ): Promise<{text: string; operationId: string}> {
+    ): Promise<{text: string; language?: string; operationId: string}> {
         const opId = operationId || getRandomId();
 
         if (options.providerId === "local") {
@@ -173,11 +174,16 @@ export class AIServiceBackend {
 
                 const transcription = await G.rustProxy.localTranscribeAudio(opId, audioData, options.model, language);
                 return {
-                    text: transcription,
+                    text: transcription.text,
+                    language: transcription.language ?? undefined,
                     operationId: opId,
                 };
             } catch (error) {
                 Logger.error("[AIServiceBackend] Local audio transcription failed", {error});
+                // Keep the typed failure so callers can offer to download a missing model
+                if (error instanceof LocalTranscriptionFailure) {
+                    throw error;
+                }
                 throw new Error(`Local audio transcription failed: ${error}`);
             }
         }
@@ -214,7 +220,8 @@ export class AIServiceBackend {
             );
 
             return {
-                text: transcription,
+                text: transcription.text,
+                language: transcription.language ?? undefined,
                 operationId: opId,
             };
         } catch (error) {
@@ -1,25 +1,77 @@
 import {invoke} from "@tauri-apps/api/core";
 import {listen} from "@tauri-apps/api/event";
 import {Logger} from "../logger/Logger.ts";
-import type {AudioTranscriptionRequest, ChatCompletionRequest, ChatCompletionResponse, ProviderCredentials} from "./interface/AITypes.ts";
-import type {AudioRecordingConfig, AudioRecordingResult, AudioRecordingSession} from "./interface/AudioTypes.ts";
-import type {LocalModelStatus} from "./interface/LocalModelTypes.ts";
+import type {AudioTranscriptionRequest, AudioTranscriptionResponse, ChatCompletionRequest, ChatCompletionResponse, DictationOutput, ProviderCredentials, ProviderFallback, RefinedTranscription, Tool, ToolLoopResponse, TranscriptionResult, TranscriptionSource} from "./interface/AITypes.ts";
+import type {AudioRecordingConfig, AudioRecordingResult, AudioRecordingSession, MicrophonePermission} from "./interface/AudioTypes.ts";
+import type {LocalModelCatalogEntry, LocalModelDownloadProgress, LocalModelStatus, LocalTranscriptionError} from "./interface/LocalModelTypes.ts";
+
+/** Thrown by local transcription; `detail.kind` tells e.g. a missing model from other failures */
+export class LocalTranscriptionFailure extends Error {
+    constructor(public readonly detail: LocalTranscriptionError) {
+        super(detail.kind === "model_not_downloaded" ? `Model ${detail.model_id} is not downloaded` : detail.message);
+        this.name = "LocalTranscriptionFailure";
+    }
+}
+
+function isLocalTranscriptionError(error: unknown): error is LocalTranscriptionError {
+    return typeof error === "object" && error !== null && "kind" in error;
+}
 
 export class RustProxy {
-    public async chatCompletion(request: ChatCompletionRequest, operationId: string, credentials: ProviderCredentials): Promise<ChatCompletionResponse> {
+    public async chatCompletion(
+        request: ChatCompletionRequest,
+        operationId: string,
+        credentials: ProviderCredentials,
+        fallbacks?: ProviderFallback[],
+    ): Promise<ChatCompletionResponse> {
         try {
-            return await invoke<ChatCompletionResponse>("chat_completion", {request, operationId, credentials});
+            return await invoke<ChatCompletionResponse>("chat_completion", {request, operationId, credentials, fallbacks});
         } catch (error) {
             Logger.error("[RustProxy] chatCompletion failed", {error});
             throw new Error(`Chat completion failed: ${error}`);
         }
     }
 
-    public async transcribeAudio(operationId: string, audioData: Uint8Array, request: AudioTranscriptionRequest, credentials: ProviderCredentials): Promise<string> {
+    public async chatCompletionVariants(request: ChatCompletionRequest, operationId: string, credentials: ProviderCredentials, n: number): Promise<string[]> {
+        try {
+            return await invoke<string[]>("chat_completion_variants", {request, operationId, credentials, n});
+        } catch (error) {
+            Logger.error("[RustProxy] chatCompletionVariants failed", {error});
+            throw new Error(`Chat completion variants failed: ${error}`);
+        }
+    }
+
+    public async chatCompletionWithTools(request: ChatCompletionRequest, operationId: string, credentials: ProviderCredentials, maxIterations?: number): Promise<ToolLoopResponse> {
+        try {
+            return await invoke<ToolLoopResponse>("chat_completion_with_tools", {request, operationId, credentials, maxIterations});
+        } catch (error) {
+            Logger.error("[RustProxy] chatCompletionWithTools failed", {error});
+            throw new Error(`Chat completion with tools failed: ${error}`);
+        }
+    }
+
+    public async mcpConnect(serverUrl: string): Promise<Tool[]> {
+        try {
+            return await invoke<Tool[]>("mcp_connect", {serverUrl});
+        } catch (error) {
+            Logger.error("[RustProxy] mcpConnect failed", {error});
+            throw new Error(`MCP connect failed: ${error}`);
+        }
+    }
+
+    public async mcpDisconnect(): Promise<void> {
+        await invoke("mcp_disconnect");
+    }
+
+    public async mcpListTools(): Promise<Tool[]> {
+        return await invoke<Tool[]>("mcp_list_tools");
+    }
+
+    public async transcribeAudio(operationId: string, audioData: Uint8Array, request: AudioTranscriptionRequest, credentials: ProviderCredentials): Promise<TranscriptionResult> {
         try {
             const audioArray = Array.from(audioData);
 
-            return await invoke<string>("transcribe_audio", {
+            return await invoke<TranscriptionResult>("transcribe_audio", {
                 operationId,
                 audioData: audioArray,
                 model: request.model,
@@ -33,6 +85,83 @@ export class RustProxy {
         }
     }
 
+    public async transcribeAndRefine(
+        operationId: string,
+        audioData: Uint8Array,
+        source: TranscriptionSource,
+        model: string,
+        credentials: ProviderCredentials,
+        language?: string,
+        systemPrompt?: string,
+    ): Promise<RefinedTranscription> {
+        try {
+            return await invoke<RefinedTranscription>("transcribe_and_refine", {
+                operationId,
+                audioData: Array.from(audioData),
+                source,
+                language,
+                model,
+                credentials,
+                systemPrompt,
+            });
+        } catch (error) {
+            Logger.error("[RustProxy] transcribeAndRefine failed", {error});
+            if (isLocalTranscriptionError(error)) {
+                throw new LocalTranscriptionFailure(error);
+            }
+            throw new Error(`Transcription and refinement failed: ${error}`);
+        }
+    }
+
+    public async dictate(
+        sessionId: string,
+        operationId: string,
+        source: TranscriptionSource,
+        language?: string,
+        output?: DictationOutput,
+    ): Promise<string> {
+        try {
+            return await invoke<string>("dictate", {
+                sessionId,
+                operationId,
+                source,
+                language,
+                output,
+            });
+        } catch (error) {
+            Logger.error("[RustProxy] dictate failed", {error});
+            if (isLocalTranscriptionError(error)) {
+                throw new LocalTranscriptionFailure(error);
+            }
+            throw new Error(`Dictation failed: ${error}`);
+        }
+    }
+
+    public async transcribeAudioVerbose(
+        operationId: string,
+        audioData: Uint8Array,
+        request: AudioTranscriptionRequest,
+        credentials: ProviderCredentials,
+        diarize?: boolean,
+    ): Promise<AudioTranscriptionResponse> {
+        try {
+            const audioArray = Array.from(audioData);
+
+            return await invoke<AudioTranscriptionResponse>("transcribe_audio_verbose", {
+                operationId,
+                audioData: audioArray,
+                model: request.model,
+                language: request.language,
+                prompt: request.prompt,
+                diarize,
+                credentials,
+            });
+        } catch (error) {
+            Logger.error("[RustProxy] transcribeAudioVerbose failed", {error});
+            throw new Error(`Audio transcription failed: ${error}`);
+        }
+    }
+
     public async abortOperation(operationId: string): Promise<void> {
         try {
             await invoke("abort_operation", {operationId});
@@ -50,8 +179,26 @@ export class RustProxy {
         }
     }
 
-    public async localModelDownload(modelId: string, onProgress: (progress: number) => void): Promise<void> {
-        const unlisten = await listen<number>(`local-model-download-progress-${modelId}`, (event) => {
+    public async refreshModelCatalog(url: string, trusted?: boolean, locale?: string): Promise<LocalModelCatalogEntry[]> {
+        try {
+            return await invoke<LocalModelCatalogEntry[]>("refresh_model_catalog", {url, trusted, locale});
+        } catch (error) {
+            Logger.error("[RustProxy] refreshModelCatalog failed", {error});
+            throw new Error(`Failed to refresh model catalog: ${error}`);
+        }
+    }
+
+    public async addCustomModel(id: string, name: string, downloadUrl: string, sha256: string): Promise<LocalModelCatalogEntry> {
+        try {
+            return await invoke<LocalModelCatalogEntry>("add_custom_model", {id, name, downloadUrl, sha256});
+        } catch (error) {
+            Logger.error("[RustProxy] addCustomModel failed", {error});
+            throw new Error(`Failed to add custom model: ${error}`);
+        }
+    }
+
+    public async localModelDownload(modelId: string, onProgress: (progress: LocalModelDownloadProgress) => void): Promise<void> {
+        const unlisten = await listen<LocalModelDownloadProgress>(`local-model-download-progress-${modelId}`, (event) => {
             onProgress(event.payload);
         });
 
@@ -98,6 +245,24 @@ export class RustProxy {
         }
     }
 
+    public async pauseAudioRecording(sessionId: string): Promise<void> {
+        try {
+            await invoke<void>("pause_audio_recording", {sessionId});
+        } catch (error) {
+            Logger.error("[RustProxy] pauseAudioRecording failed", {error});
+            throw new Error(`Failed to pause audio recording: ${error}`);
+        }
+    }
+
+    public async resumeAudioRecording(sessionId: string): Promise<void> {
+        try {
+            await invoke<void>("resume_audio_recording", {sessionId});
+        } catch (error) {
+            Logger.error("[RustProxy] resumeAudioRecording failed", {error});
+            throw new Error(`Failed to resume audio recording: ${error}`);
+        }
+    }
+
     public async resetAudioRecording(): Promise<boolean> {
         try {
             return await invoke<boolean>("reset_audio_recording");
@@ -134,6 +299,15 @@ export class RustProxy {
         }
     }
 
+    public async secureStorageDeleteByPrefix(prefix: string): Promise<number> {
+        try {
+            return await invoke<number>("secure_storage_delete_by_prefix", {prefix});
+        } catch (error) {
+            Logger.error(`[RustProxy] secureStorageDeleteByPrefix failed: ${prefix}`, {error});
+            throw new Error(`Failed to delete secure credentials: ${error}`);
+        }
+    }
+
     public async secureStorageHas(key: string): Promise<boolean> {
         try {
             return await invoke<boolean>("secure_storage_has", {key});
@@ -168,6 +342,24 @@ export class RustProxy {
         }
     }
 
+    public async checkMicrophonePermission(): Promise<MicrophonePermission> {
+        try {
+            return await invoke<MicrophonePermission>("check_microphone_permission");
+        } catch (error) {
+            Logger.error("[RustProxy] checkMicrophonePermission failed", {error});
+            throw error;
+        }
+    }
+
+    public async requestMicrophonePermission(): Promise<MicrophonePermission> {
+        try {
+            return await invoke<MicrophonePermission>("request_microphone_permission");
+        } catch (error) {
+            Logger.error("[RustProxy] requestMicrophonePermission failed", {error});
+            throw error;
+        }
+    }
+
     public async simulatePaste(): Promise<void> {
         try {
             await invoke("simulate_paste");
@@ -177,28 +369,72 @@ export class RustProxy {
         }
     }
 
-    public async fetchProviderModels(apiKey: string, baseUrl: string): Promise<{id: string; object: string; owned_by?: string}[]> {
+    public async fetchProviderModels(
+        apiKey: string,
+        baseUrl: string,
+        operationId?: string,
+        includePatterns?: string[],
+        excludePatterns?: string[],
+        extraHeaders?: Record<string, string>,
+        timeoutSecs?: number,
+    ): Promise<{id: string; object: string; owned_by?: string}[]> {
         try {
-            return await invoke<{id: string; object: string; owned_by?: string}[]>("fetch_provider_models", {apiKey, baseUrl});
+            return await invoke<{id: string; object: string; owned_by?: string}[]>("fetch_provider_models", {
+                apiKey,
+                baseUrl,
+                operationId,
+                includePatterns,
+                excludePatterns,
+                extraHeaders,
+                timeoutSecs,
+            });
         } catch (error) {
             Logger.error("[RustProxy] fetchProviderModels failed", {error});
             throw error;
         }
     }
 
-    public async localTranscribeAudio(operationId: string, audioData: Uint8Array, modelId: string, language?: string): Promise<string> {
+    public async localTranscribeAudio(
+        operationId: string,
+        audioData: Uint8Array,
+        modelId: string,
+        language?: string,
+        translate?: boolean,
+        threads?: number,
+        beamSize?: number,
+        gpu?: boolean,
+    ): Promise<TranscriptionResult> {
         try {
             const audioArray = Array.from(audioData);
 
-            return await invoke<string>("local_transcribe_audio", {
+            return await invoke<TranscriptionResult>("local_transcribe_audio", {
                 operationId,
                 audioData: audioArray,
                 modelId,
                 language,
+                translate,
+                threads,
+                beamSize,
+                gpu,
             });
         } catch (error) {
             Logger.error("[RustProxy] localTranscribeAudio failed", {error});
+            if (isLocalTranscriptionError(error)) {
+                throw new LocalTranscriptionFailure(error);
+            }
             throw new Error(`Local transcription failed: ${error}`);
         }
     }
+
+    public async preloadLocalModel(modelId: string, gpu?: boolean): Promise<boolean> {
+        try {
+            return await invoke<boolean>("preload_local_model", {modelId, gpu});
+        } catch (error) {
+            Logger.error("[RustProxy] preloadLocalModel failed", {error});
+            if (isLocalTranscriptionError(error)) {
+                throw new LocalTranscriptionFailure(error);
+            }
+            throw new Error(`Failed to preload local model: ${error}`);
+        }
+    }
 }
@@ -1,3 +1,5 @@
+import type {LocalTranscriptionError} from "./LocalModelTypes.ts";
+
 export interface ChatCompletionRequest {
     model: string;
     messages: any[];
@@ -6,10 +8,24 @@ export interface ChatCompletionRequest {
     tools?: any[];
     tool_ids?: string[];
     stream?: boolean;
-    response_format?: {type: "json_object" | "text"};
+    response_format?: ResponseFormat;
     reasoning_effort?: string;
+    force_reasoning?: boolean;
+    parallel_tool_calls?: boolean;
+    modalities?: ("text" | "audio")[];
+    audio?: {voice: string; format: string};
+    top_p?: number;
+    frequency_penalty?: number;
+    presence_penalty?: number;
+    seed?: number;
+    stop?: string | string[];
+    n?: number;
 }
 
+export type ResponseFormat =
+    | {type: "json_object" | "text"}
+    | {type: "json_schema"; json_schema: {name: string; description?: string; schema: Record<string, unknown>; strict?: boolean}};
+
 export interface ChatCompletionResponse {
     id: string;
     object: string;
@@ -27,6 +43,23 @@ export interface ChatCompletionResponse {
     };
     citations?: string[];
     search_results?: any;
+    cached?: boolean;
+    provider_index?: number;
+}
+
+export interface Tool {
+    type: "function";
+    function: {
+        name: string;
+        description?: string | null;
+        parameters: Record<string, unknown>;
+    };
+}
+
+export interface ToolLoopResponse {
+    response: ChatCompletionResponse;
+    messages: any[];
+    iterations: number;
 }
 
 export interface AudioTranscriptionRequest {
@@ -35,6 +68,60 @@ export interface AudioTranscriptionRequest {
     prompt?: string;
 }
 
+export interface TranscriptionSegment {
+    id: number;
+    start: number;
+    end: number;
+    text: string;
+    speaker?: string;
+    [key: string]: any;
+}
+
+export interface TranscriptionWord {
+    word: string;
+    start: number;
+    end: number;
+}
+
+export interface AudioTranscriptionResponse {
+    text: string;
+    language?: string;
+    duration?: number;
+    words?: TranscriptionWord[];
+    segments?: TranscriptionSegment[];
+}
+
+export interface ProviderFallback {
+    credentials: ProviderCredentials;
+    model?: string;
+}
+
+export interface TranscriptionResult {
+    text: string;
+    language?: string | null;
+    duration_ms?: number | null;
+    backend?: "cpu" | "metal" | "cuda";
+}
+
+export type TranscriptionSource = {type: "local"; model_id: string} | {type: "cloud"; model: string; credentials: ProviderCredentials};
+
+export interface RefinedTranscription {
+    raw_text: string;
+    refined_text: string;
+    language: string | null;
+}
+
+export type DictationOutput = "paste" | "type";
+
+/** Payload of the "dictation-state" event emitted by `dictate` and the dictation hotkey */
+export type DictationState =
+    | {state: "recording"; session_id: string}
+    | {state: "transcribing"; session_id: string}
+    | {state: "pasting"; session_id: string}
+    | {state: "done"; session_id: string; text: string}
+    | {state: "aborted"; session_id: string}
+    | {state: "failed"; session_id: string; error: LocalTranscriptionError};
+
 export interface TextToSpeechRequest {
     model: string;
     text: string;
@@ -45,4 +132,11 @@ export interface TextToSpeechRequest {
 export interface ProviderCredentials {
     api_key: string;
     base_url: string;
+    provider_kind?: "openai" | "anthropic" | "azure";
+    api_version?: string;
+    max_audio_upload_mb?: number;
+    timeout_secs?: number;
+    stream_idle_timeout_secs?: number;
+    extra_headers?: Record<string, string>;
+    stream_usage?: boolean;
 }
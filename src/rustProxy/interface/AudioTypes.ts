@@ -3,13 +3,23 @@ export interface AudioRecordingSession {
     started_at: number;
     sample_rate: number;
     channels: number;
+    device_name: string | null;
 }
 
 export interface AudioRecordingResult {
     session_id: string;
     duration_ms: number;
     audio_data: number[];
+    output_format: AudioOutputFormat;
     sample_rate: number;
+    is_empty: boolean;
+    applied_gain_db: number;
+    waveform: WaveformBucket[];
+}
+
+export interface WaveformBucket {
+    peak: number;
+    rms: number;
 }
 
 export interface AudioRecordingConfig {
@@ -18,4 +28,29 @@ export interface AudioRecordingConfig {
     echo_cancellation?: boolean;
     noise_suppression?: boolean;
     auto_gain_control?: boolean;
+    min_duration_ms?: number;
+    high_pass_hz?: number | null;
+    device_name?: string | null;
+    silence_timeout_ms?: number | null;
+    silence_threshold?: number | null;
+    max_duration_ms?: number | null;
+    source?: AudioSource;
+    target_peak_dbfs?: number | null;
+    trim_silence?: boolean;
+    output_format?: AudioOutputFormat;
+    waveform_buckets?: number;
 }
+
+export type AudioOutputFormat = "wav" | "flac" | "opus";
+
+export type AudioSource = "microphone" | "system_loopback";
+
+export interface AudioInputDevice {
+    name: string;
+    is_default: boolean;
+    default_sample_rate: number | null;
+    default_channels: number | null;
+    supported_channels: number[];
+}
+
+export type MicrophonePermission = "granted" | "denied" | "undetermined" | "not_applicable";
@@ -7,7 +7,61 @@ export interface LocalModelStatus {
     downloaded: boolean;
     downloading: boolean;
     download_progress: number;
+    partial_progress?: number;
     speed_rating: number;
     accuracy_rating: number;
     language_support: "english-only" | "multilingual";
 }
+
+export interface LocalModelCatalogEntry {
+    id: string;
+    name: string;
+    category: "speech-to-text";
+    description: string;
+    size_mb: number;
+    download_url: string;
+    filename: string;
+    speed_rating: number;
+    accuracy_rating: number;
+    language_support: string;
+    sha256?: string;
+}
+
+export interface LocalModelDownloadProgress {
+    downloaded_bytes: number;
+    total_bytes: number;
+    percent: number;
+    bytes_per_second: number;
+    eta_seconds: number | null;
+}
+
+export type LocalTranscriptionError =
+    | {kind: "model_not_downloaded"; model_id: string; size_mb: number | null}
+    | {kind: "model_corrupt"; message: string}
+    | {kind: "decode_failed"; message: string}
+    | {kind: "inference_failed"; message: string}
+    | {kind: "failed"; message: string};
+
+export interface LiveTranscript {
+    stable_text: string;
+    text: string;
+    is_final: boolean;
+}
+
+export interface TranscriptWord {
+    word: string;
+    start_ms: number;
+    end_ms: number;
+}
+
+export interface TranscriptSegment {
+    start_ms: number;
+    end_ms: number;
+    text: string;
+    words?: TranscriptWord[];
+}
+
+export interface SegmentedTranscript {
+    text: string;
+    segments: TranscriptSegment[];
+}
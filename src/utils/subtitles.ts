@@ -0,0 +1,22 @@
+import type {TranscriptionSegment} from "../rustProxy/interface/AITypes.ts";
+
+/** Format seconds as a subtitle timestamp, `HH:MM:SS{separator}mmm` */
+const subtitleTime = (seconds: number, separator: string) => {
+    const ms = Math.max(0, Math.round(seconds * 1000));
+    const pad = (value: number, length = 2) => String(value).padStart(length, "0");
+    return `${pad(Math.floor(ms / 3_600_000))}:${pad(Math.floor(ms / 60_000) % 60)}:${pad(Math.floor(ms / 1000) % 60)}${separator}${pad(ms % 1000, 3)}`;
+};
+
+const cues = (segments: TranscriptionSegment[]) => segments.map((segment) => ({...segment, text: segment.text.trim()})).filter((segment) => segment.text.length > 0);
+
+/** SubRip subtitles from `verbose_json` segments (same layout as local `srt` output) */
+export const segmentsToSrt = (segments: TranscriptionSegment[]) => {
+    return cues(segments)
+        .map((segment, index) => `${index + 1}\n${subtitleTime(segment.start, ",")} --> ${subtitleTime(segment.end, ",")}\n${segment.text}\n`)
+        .join("\n");
+};
+
+/** WebVTT subtitles from `verbose_json` segments (same layout as local `vtt` output) */
+export const segmentsToVtt = (segments: TranscriptionSegment[]) => {
+    return cues(segments).reduce((vtt, segment) => `${vtt}\n${subtitleTime(segment.start, ".")} --> ${subtitleTime(segment.end, ".")}\n${segment.text}\n`, "WEBVTT\n");
+};
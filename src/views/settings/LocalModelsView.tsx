@@ -35,7 +35,7 @@ export function LocalModelsView() {
 
         try {
             await G.rustProxy.localModelDownload(modelId, (progress) => {
-                setDownloadingModels((prev) => ({...prev, [modelId]: progress}));
+                setDownloadingModels((prev) => ({...prev, [modelId]: progress.percent}));
             });
             await fetchModels();
         } catch (error) {
@@ -427,6 +427,16 @@ export class VoiceModule {
             }
 
             const result = await G.rustProxy.stopAudioRecording(this.currentSession.session_id);
+
+            if (result.is_empty) {
+                this.currentSession = null;
+                this.storeManager.setRecordingState(false);
+                await this.unregisterEscapeShortcut();
+                await this.closeRecordingPopup();
+                Logger.info("[VoiceModule] Recording too short, skipping transcription");
+                return;
+            }
+
             const audioBlob = this.audioResultToBlob(result);
 
             this.currentSession = null;